@@ -0,0 +1,44 @@
+//! Batch analysis of deal numbers with the solver.
+//!
+//! ```bash
+//! cargo run --release -p solitaire-core --example solve -- [first seed] [count] [draw count]
+//! ```
+
+use solitaire_core::{Game, Rules, Solution, Solver};
+use std::env;
+
+fn main() {
+    let args: Vec<u64> = env::args()
+        .skip(1)
+        .map(|arg| arg.parse().unwrap())
+        .collect();
+    let first = args.first().copied().unwrap_or(1);
+    let count = args.get(1).copied().unwrap_or(100);
+    let rules = Rules {
+        draw_count: args.get(2).copied().unwrap_or(1) as usize,
+        ..Rules::default()
+    };
+
+    let solver = Solver::default();
+    let (mut won, mut lost, mut unknown) = (0, 0, 0);
+    for seed in first..first + count {
+        match solver.solve(&Game::new(seed, rules)) {
+            Solution::Win(moves) => {
+                won += 1;
+                println!("{}: winnable in {} moves", seed, moves.len());
+            }
+            Solution::Unwinnable => {
+                lost += 1;
+                println!("{}: unwinnable", seed);
+            }
+            Solution::Unknown => {
+                unknown += 1;
+                println!("{}: gave up", seed);
+            }
+        }
+    }
+    println!(
+        "{} winnable, {} unwinnable, {} undecided",
+        won, lost, unknown
+    );
+}
@@ -0,0 +1,186 @@
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Color {
+    Red,
+    Black,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Suit {
+    Hearts,
+    Diamonds,
+    Clubs,
+    Spades,
+}
+
+impl Suit {
+    pub const ALL: [Suit; 4] = [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades];
+
+    pub fn color(self) -> Color {
+        match self {
+            Suit::Hearts | Suit::Diamonds => Color::Red,
+            Suit::Clubs | Suit::Spades => Color::Black,
+        }
+    }
+
+    /// Position of the suit in `Suit::ALL`.
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Lower-case name, also used as the sprite directory (e.g. "hearts").
+    pub fn name(self) -> &'static str {
+        match self {
+            Suit::Hearts => "hearts",
+            Suit::Diamonds => "diamonds",
+            Suit::Clubs => "clubs",
+            Suit::Spades => "spades",
+        }
+    }
+
+    /// Upper-case initial used in card codes (e.g. 'H').
+    pub fn letter(self) -> char {
+        match self {
+            Suit::Hearts => 'H',
+            Suit::Diamonds => 'D',
+            Suit::Clubs => 'C',
+            Suit::Spades => 'S',
+        }
+    }
+
+    pub fn from_letter(letter: char) -> Option<Suit> {
+        Suit::ALL
+            .into_iter()
+            .find(|suit| suit.letter() == letter.to_ascii_uppercase())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Rank {
+    Ace = 1,
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+}
+
+impl Rank {
+    pub const ALL: [Rank; 13] = [
+        Rank::Ace,
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+    ];
+
+    /// 1 for an Ace up to 13 for a King.
+    pub fn ordinal(self) -> u8 {
+        self as u8
+    }
+
+    pub fn from_ordinal(ordinal: u8) -> Option<Rank> {
+        Rank::ALL.get((ordinal as usize).checked_sub(1)?).copied()
+    }
+
+    /// The rank directly above this one, `None` for a King.
+    pub fn successor(self) -> Option<Rank> {
+        Rank::from_ordinal(self.ordinal() + 1)
+    }
+
+    /// The rank directly below this one, `None` for an Ace.
+    pub fn predecessor(self) -> Option<Rank> {
+        Rank::from_ordinal(self.ordinal() - 1)
+    }
+
+    /// Index label as printed on the card, also used as the sprite file name (e.g. "10").
+    pub fn label(self) -> &'static str {
+        match self {
+            Rank::Ace => "A",
+            Rank::Two => "2",
+            Rank::Three => "3",
+            Rank::Four => "4",
+            Rank::Five => "5",
+            Rank::Six => "6",
+            Rank::Seven => "7",
+            Rank::Eight => "8",
+            Rank::Nine => "9",
+            Rank::Ten => "10",
+            Rank::Jack => "J",
+            Rank::Queen => "Q",
+            Rank::King => "K",
+        }
+    }
+
+    /// Single character used in card codes, with 'T' for a Ten.
+    pub fn letter(self) -> char {
+        match self {
+            Rank::Ten => 'T',
+            rank => rank.label().chars().next().unwrap(),
+        }
+    }
+
+    /// Reads a rank written as its letter or its label ("T" or "10").
+    pub fn parse(text: &str) -> Option<Rank> {
+        let text = text.to_ascii_uppercase();
+        Rank::ALL
+            .into_iter()
+            .find(|rank| text == rank.label() || text.chars().eq([rank.letter()]))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Card {
+    pub rank: Rank,
+    pub suit: Suit,
+    pub face_up: bool,
+}
+
+impl Card {
+    pub fn new(rank: Rank, suit: Suit) -> Self {
+        Card {
+            rank,
+            suit,
+            face_up: false,
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        self.suit.color()
+    }
+
+    // Helper function for checking if a card is red or black
+    pub fn is_red(&self) -> bool {
+        self.color() == Color::Red
+    }
+
+    /// Reads a card code such as "AH", "TS" or "10s" (face down).
+    pub fn parse(code: &str) -> Option<Card> {
+        let suit = Suit::from_letter(code.chars().last()?)?;
+        let rank = Rank::parse(&code[..code.len() - 1])?;
+        Some(Card::new(rank, suit))
+    }
+}
+
+/// Two-character code: rank letter then suit letter, e.g. "AH" or "TS".
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.rank.letter(), self.suit.letter())
+    }
+}
@@ -0,0 +1,564 @@
+use crate::card::{Card, Rank};
+use crate::moves::Move;
+use crate::rng::SplitMix64;
+use crate::rules::{Rules, Scoring, Variant};
+use crate::score;
+
+/// Identifies one of the piles on the board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pile {
+    Tableau(usize),
+    Foundation(usize),
+    Stock,
+    Discard,
+}
+
+/// A move as it was applied, with what is needed to take it back.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Record {
+    pub mv: Move,
+    /// Score before the move; penalties are clamped at zero, so it cannot
+    /// always be recomputed backwards.
+    pub score: i32,
+}
+
+/// A Klondike or Spider position together with the rules that act on it.
+///
+/// Nothing in here knows about canvases or coordinates; the front end
+/// decides which piles the player is pointing at and asks the game whether
+/// the move is allowed.
+#[derive(Clone, Debug)]
+pub struct Game {
+    pub tableau: Vec<Vec<Card>>,    // 7 tableau piles, 10 in Spider
+    pub foundation: Vec<Vec<Card>>, // 4 foundation piles, 8 in Spider
+    pub stock: Vec<Card>,           // Draw pile
+    pub discard: Vec<Card>,         // Discard pile
+    pub seed: Option<u64>,          // Deal number, if the deal came from a seed
+    pub rules: Rules,
+    score: i32,
+    recycles: u32,        // Times the discard pile went back to the stock
+    history: Vec<Record>, // Applied moves, oldest first
+    undone: Vec<Move>,    // Undone moves, next redo last
+}
+
+impl Game {
+    /// Every card the variant is played with: one deck for Klondike, two
+    /// for Spider, where each of the variant's suits is repeated until
+    /// there are eight.
+    pub fn create_deck(variant: Variant) -> Vec<Card> {
+        let mut deck = Vec::new();
+
+        let suits = variant.suits();
+        for _ in 0..variant.foundation_piles() / suits.len() {
+            for &suit in suits {
+                for rank in Rank::ALL {
+                    deck.push(Card::new(rank, suit));
+                }
+            }
+        }
+
+        deck
+    }
+
+    /// Deals deal number `seed`. The same seed gives the same deal on every
+    /// platform.
+    pub fn new(seed: u64, rules: Rules) -> Self {
+        let mut deck = Self::create_deck(rules.variant);
+        SplitMix64::new(seed).shuffle(&mut deck);
+
+        let mut game = Self::deal(deck, rules);
+        game.seed = Some(seed);
+        game
+    }
+
+    /// Deals a (usually shuffled) deck into the layout of the rules'
+    /// variant. Cards are taken from the end of `deck`; whatever is left
+    /// becomes the stock.
+    pub fn deal(mut deck: Vec<Card>, rules: Rules) -> Self {
+        let variant = rules.variant;
+        let mut tableau = vec![vec![]; variant.tableau_piles()];
+        for (i, pile) in tableau.iter_mut().enumerate() {
+            // Klondike piles grow by one card per column; Spider deals six
+            // cards to the first four columns and five to the rest
+            let size = match variant {
+                Variant::Klondike => i + 1,
+                Variant::Spider { .. } if i < 4 => 6,
+                Variant::Spider { .. } => 5,
+            };
+            for j in 0..size {
+                let mut card = deck.pop().unwrap();
+                card.face_up = j == size - 1; // Only the top card in each pile is face-up
+                pile.push(card);
+            }
+        }
+
+        Game {
+            tableau,
+            foundation: vec![vec![]; variant.foundation_piles()],
+            stock: deck,
+            discard: Vec::new(),
+            seed: None,
+            rules,
+            score: score::starting_score(&rules),
+            recycles: 0,
+            history: Vec::new(),
+            undone: Vec::new(),
+        }
+    }
+
+    /// A position laid out pile by pile, e.g. read back from text, with
+    /// nothing to undo. `recycles` counts the passes already used up.
+    pub(crate) fn from_piles(
+        tableau: Vec<Vec<Card>>,
+        foundation: Vec<Vec<Card>>,
+        stock: Vec<Card>,
+        discard: Vec<Card>,
+        rules: Rules,
+        score: i32,
+        recycles: u32,
+    ) -> Self {
+        Game {
+            tableau,
+            foundation,
+            stock,
+            discard,
+            seed: None,
+            rules,
+            score,
+            recycles,
+            history: Vec::new(),
+            undone: Vec::new(),
+        }
+    }
+
+    pub fn pile(&self, pile: Pile) -> &Vec<Card> {
+        match pile {
+            Pile::Tableau(i) => &self.tableau[i],
+            Pile::Foundation(i) => &self.foundation[i],
+            Pile::Stock => &self.stock,
+            Pile::Discard => &self.discard,
+        }
+    }
+
+    // Like `pile`, but `None` for an index past the piles on the board, as
+    // moves read from text may name any index
+    fn get_pile(&self, pile: Pile) -> Option<&Vec<Card>> {
+        match pile {
+            Pile::Tableau(i) => self.tableau.get(i),
+            Pile::Foundation(i) => self.foundation.get(i),
+            Pile::Stock => Some(&self.stock),
+            Pile::Discard => Some(&self.discard),
+        }
+    }
+
+    fn pile_mut(&mut self, pile: Pile) -> &mut Vec<Card> {
+        match pile {
+            Pile::Tableau(i) => &mut self.tableau[i],
+            Pile::Foundation(i) => &mut self.foundation[i],
+            Pile::Stock => &mut self.stock,
+            Pile::Discard => &mut self.discard,
+        }
+    }
+
+    /// The move performed by clicking the stock: drawing from it, or
+    /// recycling the discard pile once it is empty. In Spider it deals a
+    /// row, which is only allowed while no column is empty.
+    pub fn stock_move(&self) -> Option<Move> {
+        if let Variant::Spider { .. } = self.rules.variant {
+            Some(Move::Deal).filter(|&mv| self.is_legal(mv))
+        } else if !self.stock.is_empty() {
+            Some(Move::Draw {
+                count: self.stock.len().min(self.rules.draw_count),
+            })
+        } else if self.is_legal(Move::Recycle) {
+            Some(Move::Recycle)
+        } else {
+            None
+        }
+    }
+
+    /// Every move the player can make from the current position: each valid
+    /// run of face-up tableau cards onto every pile that accepts it, discard
+    /// and foundation cards, and the stock click. Automatic moves are left
+    /// to the game.
+    pub fn legal_moves(&self) -> Vec<Move> {
+        let tableau_count = self.tableau.len();
+        let foundation_count = self.foundation.len();
+        let mut candidates = Vec::new();
+
+        for (from, pile) in self.tableau.iter().enumerate() {
+            let face_up = pile.iter().filter(|card| card.face_up).count();
+            for count in 1..=face_up {
+                for to in 0..tableau_count {
+                    candidates.push(Move::TableauToTableau { from, to, count });
+                }
+            }
+            for to in 0..foundation_count {
+                candidates.push(Move::TableauToFoundation { from, to });
+            }
+        }
+
+        for to in 0..tableau_count {
+            candidates.push(Move::DiscardToTableau { to });
+            for from in 0..foundation_count {
+                candidates.push(Move::FoundationToTableau { from, to });
+            }
+        }
+        for to in 0..foundation_count {
+            candidates.push(Move::DiscardToFoundation { to });
+        }
+        candidates.extend(self.stock_move());
+
+        candidates.retain(|&mv| self.is_legal(mv));
+        candidates
+    }
+
+    pub fn is_legal(&self, mv: Move) -> bool {
+        let spider = matches!(self.rules.variant, Variant::Spider { .. });
+        match mv {
+            Move::Draw { count } => {
+                !spider && count > 0 && count == self.stock.len().min(self.rules.draw_count)
+            }
+            Move::Recycle => {
+                !spider
+                    && self.stock.is_empty()
+                    && !self.discard.is_empty()
+                    && self.recycles_left() != Some(0)
+            }
+            Move::Flip { pile } => self
+                .tableau
+                .get(pile)
+                .and_then(|pile| pile.last())
+                .is_some_and(|card| !card.face_up),
+            // A row goes onto every column, so none may be empty
+            Move::Deal => {
+                spider
+                    && self.stock.len() >= self.tableau.len()
+                    && self.tableau.iter().all(|pile| !pile.is_empty())
+            }
+            Move::CompleteRun { from, to } => match self.tableau.get(from) {
+                Some(source) => {
+                    spider
+                        && self.foundation.get(to).is_some_and(Vec::is_empty)
+                        && source.len() >= 13
+                        && self.is_complete_run(&source[source.len() - 13..])
+                }
+                None => false,
+            },
+            _ => match mv.transfer() {
+                Some((from, count, to)) => self.can_move(from, count, to),
+                None => false,
+            },
+        }
+    }
+
+    /// How many more times the discard pile may be recycled, `None` when
+    /// there is no pass limit.
+    pub fn recycles_left(&self) -> Option<u32> {
+        let max = self.rules.pass_limit.max_recycles()?;
+        Some(max.saturating_sub(self.recycles))
+    }
+
+    /// Score under the game's scoring rules, before any time bonus.
+    pub fn score(&self) -> i32 {
+        match self.rules.scoring {
+            Scoring::Standard => self.score,
+            Scoring::Vegas => score::vegas_score(self),
+        }
+    }
+
+    /// Times the discard pile has been turned back into the stock.
+    pub fn recycles(&self) -> u32 {
+        self.recycles
+    }
+
+    /// Checks whether the top `count` cards of `from` may be placed on `to`.
+    fn can_move(&self, from: Pile, count: usize, to: Pile) -> bool {
+        if from == to || count == 0 {
+            return false;
+        }
+
+        let (Some(source), Some(target_pile)) = (self.get_pile(from), self.get_pile(to)) else {
+            return false;
+        };
+        if count > source.len() {
+            return false;
+        }
+        let cards = &source[source.len() - count..];
+        if !cards.iter().all(|card| card.face_up) || !self.is_valid_run(cards) {
+            return false;
+        }
+
+        let card = &cards[0];
+        if let Variant::Spider { .. } = self.rules.variant {
+            // Runs only leave the board whole, through `Move::CompleteRun`
+            return match (from, to) {
+                (Pile::Tableau(_), Pile::Tableau(_)) => match target_pile.last() {
+                    // Any suit will do, but only a run of one suit moves as a unit
+                    Some(target) => target.face_up && card.rank.successor() == Some(target.rank),
+                    None => true,
+                },
+                _ => false,
+            };
+        }
+        match to {
+            Pile::Foundation(_) => match target_pile.last() {
+                Some(target) => Self::is_valid_foundation_move(card, target),
+                // Allow only Aces to start a foundation pile
+                None => card.rank == Rank::Ace,
+            },
+            Pile::Tableau(_) => match target_pile.last() {
+                Some(target) => target.face_up && Self::is_valid_tableau_move(card, target),
+                // Allow only Kings to start an empty tableau pile
+                None => card.rank == Rank::King,
+            },
+            Pile::Stock | Pile::Discard => false,
+        }
+    }
+
+    /// Applies `mv` if it is legal and records it for undo. A Spider run
+    /// finished by the move is lifted off as a separate `Move::CompleteRun`,
+    /// and a tableau card left face down on top is turned over as a
+    /// separate `Move::Flip`, so undo can put both back. The game makes
+    /// those automatic moves itself and refuses them here.
+    pub fn apply(&mut self, mv: Move) -> bool {
+        if mv.is_automatic() || !self.is_legal(mv) {
+            return false;
+        }
+
+        self.record(mv);
+        self.undone.clear();
+
+        let piles = match mv.transfer() {
+            Some((from, _, to)) => vec![from, to],
+            None if mv == Move::Deal => (0..self.tableau.len()).map(Pile::Tableau).collect(),
+            None => Vec::new(),
+        };
+        for pile in piles {
+            if let Pile::Tableau(pile) = pile {
+                self.follow_up(pile);
+            }
+        }
+
+        true
+    }
+
+    // Records the automatic moves a change to tableau pile `pile` calls for
+    fn follow_up(&mut self, pile: usize) {
+        if let Some(to) = self.foundation.iter().position(Vec::is_empty) {
+            let complete = Move::CompleteRun { from: pile, to };
+            if self.is_legal(complete) {
+                self.record(complete);
+            }
+        }
+        let flip = Move::Flip { pile };
+        if self.is_legal(flip) {
+            self.record(flip);
+        }
+    }
+
+    /// Takes back the last move, together with the automatic moves it
+    /// triggered.
+    pub fn undo(&mut self) -> bool {
+        let Some(mut mv) = self.unrecord() else {
+            return false;
+        };
+        while mv.is_automatic() {
+            match self.unrecord() {
+                Some(earlier) => mv = earlier,
+                None => break,
+            }
+        }
+
+        true
+    }
+
+    /// Replays the last undone move, together with the automatic moves it
+    /// triggered.
+    pub fn redo(&mut self) -> bool {
+        let Some(mv) = self.undone.pop() else {
+            return false;
+        };
+        self.record(mv);
+
+        while let Some(&automatic) = self.undone.last().filter(|mv| mv.is_automatic()) {
+            self.undone.pop();
+            self.record(automatic);
+        }
+
+        true
+    }
+
+    // Performs a legal move and scores it
+    fn record(&mut self, mv: Move) {
+        let score = self.score;
+        self.score = (score + score::standard_points(mv, &self.rules)).max(0);
+        self.perform(mv);
+        self.history.push(Record { mv, score });
+    }
+
+    // Reverts the last recorded move onto the redo stack
+    fn unrecord(&mut self) -> Option<Move> {
+        let record = self.history.pop()?;
+        self.revert(record.mv);
+        self.score = record.score;
+        self.undone.push(record.mv);
+        Some(record.mv)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.history.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// Every move applied so far, oldest first, including automatic moves.
+    pub fn history(&self) -> &[Record] {
+        &self.history
+    }
+
+    /// Moves taken back by undo, next redo last, including automatic moves.
+    pub fn undone(&self) -> &[Move] {
+        &self.undone
+    }
+
+    // Carries out a move that is known to be legal
+    fn perform(&mut self, mv: Move) {
+        match mv {
+            Move::Draw { count } => {
+                for _ in 0..count {
+                    let mut card = self.stock.pop().unwrap();
+                    card.face_up = true;
+                    self.discard.push(card);
+                }
+            }
+            Move::Recycle => {
+                while let Some(mut card) = self.discard.pop() {
+                    card.face_up = false;
+                    self.stock.push(card);
+                }
+                self.recycles += 1;
+            }
+            Move::Flip { pile } => {
+                if let Some(card) = self.tableau[pile].last_mut() {
+                    card.face_up = true;
+                }
+            }
+            Move::Deal => {
+                for pile in self.tableau.iter_mut() {
+                    let mut card = self.stock.pop().unwrap();
+                    card.face_up = true;
+                    pile.push(card);
+                }
+            }
+            _ => {
+                let (from, count, to) = mv.transfer().unwrap();
+                self.transfer(from, count, to);
+            }
+        }
+    }
+
+    // Exactly undoes `perform`
+    fn revert(&mut self, mv: Move) {
+        match mv {
+            Move::Draw { count } => {
+                for _ in 0..count {
+                    let mut card = self.discard.pop().unwrap();
+                    card.face_up = false;
+                    self.stock.push(card);
+                }
+            }
+            Move::Recycle => {
+                while let Some(mut card) = self.stock.pop() {
+                    card.face_up = true;
+                    self.discard.push(card);
+                }
+                self.recycles -= 1;
+            }
+            Move::Flip { pile } => {
+                if let Some(card) = self.tableau[pile].last_mut() {
+                    card.face_up = false;
+                }
+            }
+            Move::Deal => {
+                for pile in self.tableau.iter_mut().rev() {
+                    let mut card = pile.pop().unwrap();
+                    card.face_up = false;
+                    self.stock.push(card);
+                }
+            }
+            _ => {
+                let (from, count, to) = mv.transfer().unwrap();
+                self.transfer(to, count, from);
+            }
+        }
+    }
+
+    fn transfer(&mut self, from: Pile, count: usize, to: Pile) {
+        let source = self.pile_mut(from);
+        let cards = source.split_off(source.len() - count);
+        self.pile_mut(to).extend(cards);
+    }
+
+    // A run may only be moved as a whole if it already forms a valid tableau
+    // sequence: alternating colours in Klondike, a single suit in Spider
+    fn is_valid_run(&self, cards: &[Card]) -> bool {
+        cards.windows(2).all(|pair| match self.rules.variant {
+            Variant::Klondike => Self::is_valid_tableau_move(&pair[1], &pair[0]),
+            Variant::Spider { .. } => {
+                pair[1].rank.successor() == Some(pair[0].rank) && pair[1].suit == pair[0].suit
+            }
+        })
+    }
+
+    // King down to Ace in one suit, all face up
+    fn is_complete_run(&self, cards: &[Card]) -> bool {
+        cards.len() == 13
+            && cards[0].rank == Rank::King
+            && cards.iter().all(|card| card.face_up)
+            && self.is_valid_run(cards)
+    }
+
+    pub fn is_valid_tableau_move(card: &Card, target: &Card) -> bool {
+        card.rank.successor() == Some(target.rank) && card.color() != target.color()
+    }
+
+    pub fn is_valid_foundation_move(card: &Card, target: &Card) -> bool {
+        // Ensure the card is the next in the sequence and matches the same suit
+        target.rank.successor() == Some(card.rank) && card.suit == target.suit
+    }
+
+    pub fn check_game_won(&self) -> bool {
+        // Check if all cards are in the foundation piles
+        self.foundation.iter().all(|pile| pile.len() == 13) // 13 cards per foundation pile
+    }
+
+    /// True once nothing is left to decide: the stock and discard pile are
+    /// empty and every tableau card is face up, so the remaining cards can
+    /// go to the foundations lowest first. Klondike only, as Spider runs
+    /// still have to be built by hand.
+    pub fn can_auto_complete(&self) -> bool {
+        self.rules.variant == Variant::Klondike
+            && self.stock.is_empty()
+            && self.discard.is_empty()
+            && self.tableau.iter().flatten().all(|card| card.face_up)
+            && !self.check_game_won()
+    }
+
+    /// Next move of an auto-complete: the lowest tableau card that can go to
+    /// a foundation.
+    pub fn auto_complete_move(&self) -> Option<Move> {
+        self.legal_moves()
+            .into_iter()
+            .filter_map(|mv| match mv {
+                Move::TableauToFoundation { from, .. } => {
+                    Some((self.tableau[from].last()?.rank, mv))
+                }
+                _ => None,
+            })
+            .min_by_key(|&(rank, _)| rank)
+            .map(|(_, mv)| mv)
+    }
+}
@@ -0,0 +1,26 @@
+//! Headless Klondike and Spider rules: cards, piles, dealing and move
+//! validation.
+//!
+//! This crate has no browser dependencies so the rules can be exercised
+//! with a plain `cargo test`; `solitaire-wasm` renders it to a canvas.
+
+mod card;
+mod game;
+mod moves;
+pub mod notation;
+pub mod position;
+pub mod replay;
+mod rng;
+mod rules;
+mod save;
+pub mod score;
+mod solver;
+pub mod stats;
+
+pub use card::{Card, Color, Rank, Suit};
+pub use game::{Game, Pile, Record};
+pub use moves::Move;
+pub use rng::SplitMix64;
+pub use rules::{PassLimit, Rules, Scoring, Variant};
+pub use save::SavedGame;
+pub use solver::{Solution, Solver};
@@ -0,0 +1,178 @@
+use std::fmt;
+
+use crate::game::Pile;
+
+/// A single change to the board. Every mutation of a `Game` goes through
+/// `Game::apply` with one of these, which is what makes undo/redo possible.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Move {
+    /// Moves the top `count` face-up cards of one tableau pile onto another.
+    TableauToTableau {
+        from: usize,
+        to: usize,
+        count: usize,
+    },
+    TableauToFoundation {
+        from: usize,
+        to: usize,
+    },
+    DiscardToTableau {
+        to: usize,
+    },
+    DiscardToFoundation {
+        to: usize,
+    },
+    FoundationToTableau {
+        from: usize,
+        to: usize,
+    },
+    /// Turns `count` cards from the stock onto the discard pile.
+    Draw {
+        count: usize,
+    },
+    /// Turns the whole discard pile back over into the empty stock.
+    Recycle,
+    /// Turns over the face-down card at the top of a tableau pile. The game
+    /// records this automatically after a move uncovers such a card.
+    Flip {
+        pile: usize,
+    },
+    /// Spider: deals one card from the stock face up onto every column.
+    Deal,
+    /// Spider: lifts a finished run, King down to Ace in one suit, off the
+    /// top of a column onto an empty foundation. Like `Flip`, the game
+    /// records this automatically after the move that completes the run.
+    CompleteRun {
+        from: usize,
+        to: usize,
+    },
+}
+
+impl Move {
+    /// Builds the move that carries the top `count` cards of `from` onto `to`,
+    /// if the player can make such a move at all (legality is checked by the
+    /// game). A finished Spider run is never one: the game lifts it off.
+    pub fn between(from: Pile, count: usize, to: Pile) -> Option<Move> {
+        match (from, to) {
+            (Pile::Tableau(from), Pile::Tableau(to)) => {
+                Some(Move::TableauToTableau { from, to, count })
+            }
+            (Pile::Tableau(from), Pile::Foundation(to)) if count == 1 => {
+                Some(Move::TableauToFoundation { from, to })
+            }
+            (Pile::Discard, Pile::Tableau(to)) if count == 1 => Some(Move::DiscardToTableau { to }),
+            (Pile::Discard, Pile::Foundation(to)) if count == 1 => {
+                Some(Move::DiscardToFoundation { to })
+            }
+            (Pile::Foundation(from), Pile::Tableau(to)) if count == 1 => {
+                Some(Move::FoundationToTableau { from, to })
+            }
+            _ => None,
+        }
+    }
+
+    /// The source pile, number of cards and destination pile of a move that
+    /// carries cards from one pile to another face up.
+    pub fn transfer(self) -> Option<(Pile, usize, Pile)> {
+        match self {
+            Move::TableauToTableau { from, to, count } => {
+                Some((Pile::Tableau(from), count, Pile::Tableau(to)))
+            }
+            Move::TableauToFoundation { from, to } => {
+                Some((Pile::Tableau(from), 1, Pile::Foundation(to)))
+            }
+            Move::DiscardToTableau { to } => Some((Pile::Discard, 1, Pile::Tableau(to))),
+            Move::DiscardToFoundation { to } => Some((Pile::Discard, 1, Pile::Foundation(to))),
+            Move::FoundationToTableau { from, to } => {
+                Some((Pile::Foundation(from), 1, Pile::Tableau(to)))
+            }
+            Move::CompleteRun { from, to } => Some((Pile::Tableau(from), 13, Pile::Foundation(to))),
+            Move::Draw { .. } | Move::Recycle | Move::Flip { .. } | Move::Deal => None,
+        }
+    }
+
+    /// True for the moves the game records by itself after another move,
+    /// rather than ones the player makes.
+    pub fn is_automatic(self) -> bool {
+        matches!(self, Move::Flip { .. } | Move::CompleteRun { .. })
+    }
+}
+
+// Short pile names used in move notation: t0-t9, f0-f7 and w for the waste
+fn pile_name(pile: Pile) -> String {
+    match pile {
+        Pile::Tableau(i) => format!("t{}", i),
+        Pile::Foundation(i) => format!("f{}", i),
+        Pile::Stock => "s".to_string(),
+        Pile::Discard => "w".to_string(),
+    }
+}
+
+fn parse_pile(name: &str) -> Option<Pile> {
+    match name {
+        "s" => Some(Pile::Stock),
+        "w" => Some(Pile::Discard),
+        _ => {
+            let index = name.get(1..)?.parse().ok()?;
+            match name.as_bytes()[0] {
+                b't' => Some(Pile::Tableau(index)),
+                b'f' => Some(Pile::Foundation(index)),
+                _ => None,
+            }
+        }
+    }
+}
+
+/// Compact notation, e.g. `t3>t5*2` (two cards from the fourth tableau pile
+/// to the sixth), `w>f0`, `draw3`, `recycle`, `flip4`, `deal` or
+/// `t2>f0*13` for a finished Spider run. `Move::parse` reads it back.
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Move::Draw { count } => write!(f, "draw{}", count),
+            Move::Recycle => write!(f, "recycle"),
+            Move::Deal => write!(f, "deal"),
+            Move::Flip { pile } => write!(f, "flip{}", pile),
+            mv => {
+                let (from, count, to) = mv.transfer().unwrap();
+                write!(f, "{}>{}", pile_name(from), pile_name(to))?;
+                if count > 1 {
+                    write!(f, "*{}", count)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Move {
+    /// Reads a move written by its `Display` notation.
+    pub fn parse(text: &str) -> Option<Move> {
+        if text == "recycle" {
+            return Some(Move::Recycle);
+        }
+        if text == "deal" {
+            return Some(Move::Deal);
+        }
+        if let Some(count) = text.strip_prefix("draw") {
+            return Some(Move::Draw {
+                count: count.parse().ok()?,
+            });
+        }
+        if let Some(pile) = text.strip_prefix("flip") {
+            return Some(Move::Flip {
+                pile: pile.parse().ok()?,
+            });
+        }
+
+        let (from, rest) = text.split_once('>')?;
+        let (to, count) = match rest.split_once('*') {
+            Some((to, count)) => (to, count.parse().ok()?),
+            None => (rest, 1),
+        };
+        match (parse_pile(from)?, count, parse_pile(to)?) {
+            (Pile::Tableau(from), 13, Pile::Foundation(to)) => Some(Move::CompleteRun { from, to }),
+            (from, count, to) => Move::between(from, count, to),
+        }
+    }
+}
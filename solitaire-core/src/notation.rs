@@ -0,0 +1,204 @@
+//! Deals in the PySol-style listing read and written by other Klondike
+//! solvers, for cross-checking results:
+//!
+//! ```text
+//! Foundations: H-0 D-0 C-0 S-0
+//! Talon: 8H KH 6S 3S 9D 7S QS AH 2D TH 9H 2H TS 6C 7C JC 9S 5D 6D 6H 9C 4S KS 8S
+//! : JH
+//! : JS AD
+//! : 3H QC KC
+//! : JD 3D 5H 4C
+//! : AS 5C 4H AC 7D
+//! : 7H QH 4D 2C 3C KD
+//! : 5S QD TD 8C 8D 2S TC
+//! ```
+//!
+//! Each `:` line is a tableau pile listed bottom first, of which only the
+//! last card is dealt face up. `Talon` is the stock, the card drawn first
+//! coming first. Ranks are `A`, `2`-`9`, `T` (or `10`), `J`, `Q`, `K` and
+//! suits `H`, `D`, `C`, `S`, in either case. The `Foundations` line is
+//! optional since a fresh deal has nothing on them.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::card::{Card, Suit};
+use crate::game::Game;
+use crate::rules::{Rules, Variant};
+
+const TABLEAU_PILES: usize = 7;
+const STOCK_CARDS: usize = 24;
+
+/// Why text could not be read as a deal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NotationError {
+    /// A line that is neither a pile nor a known header, with its 1-based
+    /// number.
+    UnknownLine(usize),
+    /// A word that is not a card code, and the line it is on.
+    BadCard { line: usize, code: String },
+    /// The deal does not have seven tableau piles.
+    TableauPiles(usize),
+    /// A tableau pile, counted from 0, without the number of cards it is
+    /// dealt.
+    PileSize { pile: usize, len: usize },
+    /// The talon does not hold the 24 cards left after dealing.
+    TalonSize(usize),
+    /// Cards on the foundations, which a deal never has.
+    Foundations,
+    /// A card that appears more than once.
+    Duplicate(Card),
+}
+
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NotationError::UnknownLine(line) => write!(f, "line {}: not part of a deal", line),
+            NotationError::BadCard { line, code } => {
+                write!(f, "line {}: \"{}\" is not a card", line, code)
+            }
+            NotationError::TableauPiles(piles) => write!(
+                f,
+                "expected {} tableau piles, found {}",
+                TABLEAU_PILES, piles
+            ),
+            NotationError::PileSize { pile, len } => write!(
+                f,
+                "tableau pile {} has {} cards instead of {}",
+                pile + 1,
+                len,
+                pile + 1
+            ),
+            NotationError::TalonSize(len) => {
+                write!(f, "talon has {} cards instead of {}", len, STOCK_CARDS)
+            }
+            NotationError::Foundations => write!(f, "a deal has no cards on the foundations"),
+            NotationError::Duplicate(card) => write!(f, "{} appears more than once", card),
+        }
+    }
+}
+
+impl std::error::Error for NotationError {}
+
+/// Lists the deal `game` started from, or `None` if it began from a
+/// position that was not a fresh deal.
+pub fn export_deal(game: &Game) -> Option<String> {
+    let mut start = game.clone();
+    while start.undo() {}
+    if !is_fresh_deal(&start) {
+        return None;
+    }
+
+    let cards = |cards: &mut dyn Iterator<Item = &Card>| {
+        cards
+            .map(|card| card.to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let foundations = Suit::ALL
+        .iter()
+        .map(|suit| format!("{}-0", suit.letter()))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut text = format!("Foundations: {}\n", foundations);
+    text += &format!("Talon: {}\n", cards(&mut start.stock.iter().rev()));
+    for pile in &start.tableau {
+        text += &format!(": {}\n", cards(&mut pile.iter()));
+    }
+    Some(text)
+}
+
+/// Reads a deal listing into a game played under `rules`. The listing is
+/// always a Klondike deal, whatever variant `rules` name.
+pub fn import_deal(text: &str, rules: Rules) -> Result<Game, NotationError> {
+    let mut tableau = Vec::new();
+    let mut stock = Vec::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let number = i + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(foundations) = line.strip_prefix("Foundations:") {
+            // Only empty foundations, e.g. "H-0", make sense in a deal
+            if foundations.split_whitespace().any(|f| !f.ends_with("-0")) {
+                return Err(NotationError::Foundations);
+            }
+        } else if let Some(talon) = line.strip_prefix("Talon:") {
+            stock = parse_cards(number, talon)?;
+            stock.reverse();
+        } else if let Some(pile) = line.strip_prefix(':') {
+            let mut pile = parse_cards(number, pile)?;
+            if let Some(top) = pile.last_mut() {
+                top.face_up = true;
+            }
+            tableau.push(pile);
+        } else {
+            return Err(NotationError::UnknownLine(number));
+        }
+    }
+
+    if tableau.len() != TABLEAU_PILES {
+        return Err(NotationError::TableauPiles(tableau.len()));
+    }
+    for (pile, cards) in tableau.iter().enumerate() {
+        if cards.len() != pile + 1 {
+            return Err(NotationError::PileSize {
+                pile,
+                len: cards.len(),
+            });
+        }
+    }
+    if stock.len() != STOCK_CARDS {
+        return Err(NotationError::TalonSize(stock.len()));
+    }
+
+    // With the right number of cards in place, no duplicate means none missing
+    let mut seen = HashSet::new();
+    for card in tableau.iter().flatten().chain(&stock) {
+        if !seen.insert((card.rank, card.suit)) {
+            return Err(NotationError::Duplicate(*card));
+        }
+    }
+
+    Ok(Game::from_piles(
+        tableau,
+        vec![vec![]; 4],
+        stock,
+        Vec::new(),
+        Rules {
+            variant: Variant::Klondike,
+            ..rules
+        },
+        0,
+        0,
+    ))
+}
+
+fn parse_cards(line: usize, text: &str) -> Result<Vec<Card>, NotationError> {
+    text.split_whitespace()
+        .map(|code| {
+            Card::parse(code).ok_or_else(|| NotationError::BadCard {
+                line,
+                code: code.to_string(),
+            })
+        })
+        .collect()
+}
+
+// Laid out as `Game::deal` leaves it, before any move
+fn is_fresh_deal(game: &Game) -> bool {
+    game.foundation.iter().all(Vec::is_empty)
+        && game.discard.is_empty()
+        && game.stock.len() == STOCK_CARDS
+        && game.tableau.len() == TABLEAU_PILES
+        && game.tableau.iter().enumerate().all(|(i, pile)| {
+            pile.len() == i + 1
+                && pile
+                    .iter()
+                    .enumerate()
+                    .all(|(j, card)| card.face_up == (j == i))
+        })
+}
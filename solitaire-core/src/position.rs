@@ -0,0 +1,317 @@
+//! Plain-text form of a complete position, for bug reports, test fixtures
+//! and sharing puzzles.
+//!
+//! ```text
+//! solitaire-position 2
+//! # Lines starting with '#' are comments
+//! rules variant=klondike draw=1 passes=0 scoring=standard
+//! score 15
+//! recycles 0
+//! s 4C 9D 2S JH
+//! w 3H
+//! f0 AH 2H
+//! f1
+//! f2 AC
+//! f3
+//! t0 KS QH
+//! t1 7C | 6H
+//! ...
+//! t6 9S 5D 8H 2C | QC JD TS
+//! ```
+//!
+//! The first line names the format and its version. Every other line is a
+//! key followed by values: `rules` in the same form as the page URL
+//! options, the `score` (only informative in Vegas games, whose money is
+//! worked out from the foundations), how many `recycles` of the waste are
+//! used up, then one line per pile listing its cards bottom first as rank
+//! and suit letters, with `T` for a Ten. `s` is the stock (face down), `w`
+//! the waste (face up), `f0` to `f3` the foundations and `t0` to `t6` the
+//! tableau, where the cards before a `|` are face down and the ones after
+//! it face up; a pile without `|` is all face up, and a face-down top card
+//! is read as turned over, as play leaves it. Spider keeps its `w` line
+//! empty and has foundations `f0` to `f7`, each empty or holding a finished
+//! run King first, and columns `t0` to `t9`, none of them ending in one.
+//! Lines may come in any order, but every key must be present and the cards
+//! of the variant's deck must each appear exactly once.
+//!
+//! When the format changes, `VERSION` goes up and an entry in `MIGRATIONS`
+//! rewrites positions of the previous version, so older text keeps loading.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::card::{Card, Rank, Suit};
+use crate::game::Game;
+use crate::moves::Move;
+use crate::rules::{Rules, Variant};
+
+const FORMAT: &str = "solitaire-position";
+const VERSION: u32 = 2;
+
+// Entry `i` upgrades the lines of a version `i + 1` position to version
+// `i + 2`.
+const MIGRATIONS: [fn(Vec<String>) -> Vec<String>; VERSION as usize - 1] = [name_variant];
+
+// Version 2 added Spider; every earlier position is Klondike
+fn name_variant(lines: Vec<String>) -> Vec<String> {
+    lines
+        .into_iter()
+        .map(|line| match line.strip_prefix("rules") {
+            Some(options) => format!("rules variant=klondike{}", options),
+            None => line,
+        })
+        .collect()
+}
+
+/// Why text could not be read as a position.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PositionError {
+    /// The first line is not a `solitaire-position` header.
+    MissingHeader,
+    /// Written by a newer version of the game.
+    UnsupportedVersion(u32),
+    /// A line that makes no sense, with its 1-based number.
+    BadLine { line: usize, reason: String },
+    /// A required key does not appear.
+    MissingKey(String),
+    /// The cards do not make up a legal deck or foundation.
+    BadCards(String),
+}
+
+impl fmt::Display for PositionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PositionError::MissingHeader => {
+                write!(f, "not a position: expected \"{} {}\"", FORMAT, VERSION)
+            }
+            PositionError::UnsupportedVersion(version) => write!(
+                f,
+                "position version {} is newer than this game (version {})",
+                version, VERSION
+            ),
+            PositionError::BadLine { line, reason } => write!(f, "line {}: {}", line, reason),
+            PositionError::MissingKey(key) => write!(f, "missing line \"{}\"", key),
+            PositionError::BadCards(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl std::error::Error for PositionError {}
+
+/// Writes the position of `game` in the current version of the format.
+pub fn encode(game: &Game) -> String {
+    let cards = |cards: &[Card]| {
+        cards
+            .iter()
+            .map(|card| format!(" {}", card))
+            .collect::<String>()
+    };
+
+    let mut text = format!("{} {}\n", FORMAT, VERSION);
+    text += &format!("rules {}\n", game.rules);
+    text += &format!("score {}\n", game.score());
+    text += &format!("recycles {}\n", game.recycles());
+    text += &format!("s{}\n", cards(&game.stock));
+    text += &format!("w{}\n", cards(&game.discard));
+    for (i, pile) in game.foundation.iter().enumerate() {
+        text += &format!("f{}{}\n", i, cards(pile));
+    }
+    for (i, pile) in game.tableau.iter().enumerate() {
+        let hidden = pile.iter().take_while(|card| !card.face_up).count();
+        if hidden == 0 {
+            text += &format!("t{}{}\n", i, cards(pile));
+        } else {
+            let (down, up) = pile.split_at(hidden);
+            text += &format!("t{}{} |{}\n", i, cards(down), cards(up));
+        }
+    }
+    text
+}
+
+/// Reads a position written by `encode`, in this or any earlier version.
+/// The game has no deal number and nothing to undo.
+pub fn decode(text: &str) -> Result<Game, PositionError> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+    let (_, header) = lines.next().ok_or(PositionError::MissingHeader)?;
+    let version = match header.split_once(' ') {
+        Some((FORMAT, version)) => version
+            .trim()
+            .parse::<u32>()
+            .map_err(|_| PositionError::MissingHeader)?,
+        _ => return Err(PositionError::MissingHeader),
+    };
+    if version == 0 || version > VERSION {
+        return Err(PositionError::UnsupportedVersion(version));
+    }
+
+    // Bring older positions up to date, keeping line numbers for errors
+    let (numbers, mut body): (Vec<usize>, Vec<String>) = lines
+        .map(|(number, line)| (number, line.to_string()))
+        .unzip();
+    for migrate in &MIGRATIONS[version as usize - 1..] {
+        body = migrate(body);
+    }
+
+    let mut fields = HashMap::new();
+    for (i, line) in body.iter().enumerate() {
+        let number = numbers.get(i).copied().unwrap_or(0);
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        if fields.insert(key, (number, value)).is_some() {
+            return Err(bad_line(number, format!("\"{}\" appears twice", key)));
+        }
+    }
+    let mut field = |key: &str| {
+        fields
+            .remove(key)
+            .ok_or_else(|| PositionError::MissingKey(key.to_string()))
+    };
+
+    let (number, value) = field("rules")?;
+    let rules = Rules::parse(value).ok_or_else(|| bad_line(number, "unknown rules".into()))?;
+    let (number, value) = field("score")?;
+    let score = value
+        .trim()
+        .parse()
+        .map_err(|_| bad_line(number, "score is not a number".into()))?;
+    let (number, value) = field("recycles")?;
+    let recycles = value
+        .trim()
+        .parse()
+        .map_err(|_| bad_line(number, "recycles is not a number".into()))?;
+
+    let (number, value) = field("s")?;
+    let stock = parse_cards(number, value, false)?;
+    let (number, value) = field("w")?;
+    let discard = parse_cards(number, value, true)?;
+    if matches!(rules.variant, Variant::Spider { .. }) && !discard.is_empty() {
+        return Err(bad_line(number, "Spider has no waste".into()));
+    }
+
+    let mut foundation = Vec::new();
+    for i in 0..rules.variant.foundation_piles() {
+        let (number, value) = field(&format!("f{}", i))?;
+        let pile = parse_cards(number, value, true)?;
+        check_foundation(rules.variant, &pile).map_err(|reason| bad_line(number, reason))?;
+        foundation.push(pile);
+    }
+
+    let mut tableau = Vec::new();
+    for i in 0..rules.variant.tableau_piles() {
+        let (number, value) = field(&format!("t{}", i))?;
+        let mut pile = match value.split_once('|') {
+            Some((down, up)) => {
+                let mut pile = parse_cards(number, down, false)?;
+                pile.extend(parse_cards(number, up, true)?);
+                pile
+            }
+            None => parse_cards(number, value, true)?,
+        };
+        // Play would have turned a face-down top card over already
+        if let Some(top) = pile.last_mut() {
+            top.face_up = true;
+        }
+        tableau.push(pile);
+    }
+
+    if let Some((key, (number, _))) = fields.into_iter().next() {
+        return Err(bad_line(number, format!("unknown key \"{}\"", key)));
+    }
+
+    let game = Game::from_piles(tableau, foundation, stock, discard, rules, score, recycles);
+    check_deck(&game)?;
+    // Play would have lifted a finished run off already
+    if let Some(to) = game.foundation.iter().position(Vec::is_empty) {
+        for from in 0..game.tableau.len() {
+            if game.is_legal(Move::CompleteRun { from, to }) {
+                return Err(PositionError::BadCards(format!(
+                    "t{} ends in a finished run",
+                    from
+                )));
+            }
+        }
+    }
+    Ok(game)
+}
+
+fn bad_line(line: usize, reason: String) -> PositionError {
+    PositionError::BadLine { line, reason }
+}
+
+fn parse_cards(line: usize, text: &str, face_up: bool) -> Result<Vec<Card>, PositionError> {
+    text.split_whitespace()
+        .map(|code| match Card::parse(code) {
+            Some(card) => Ok(Card { face_up, ..card }),
+            None => Err(bad_line(line, format!("\"{}\" is not a card", code))),
+        })
+        .collect()
+}
+
+// Klondike foundations build up in suit from the Ace; a Spider foundation
+// holds one whole run, King down to Ace
+fn check_foundation(variant: Variant, pile: &[Card]) -> Result<(), String> {
+    for (i, card) in pile.iter().enumerate() {
+        let expected = match variant {
+            Variant::Klondike => Rank::from_ordinal(i as u8 + 1),
+            Variant::Spider { .. } => Rank::from_ordinal(13 - i as u8),
+        };
+        if Some(card.rank) != expected || card.suit != pile[0].suit {
+            return Err(format!("foundation cannot hold {} there", card));
+        }
+    }
+    if let Variant::Spider { .. } = variant {
+        if !pile.is_empty() && pile.len() != 13 {
+            return Err("foundation holds part of a run".to_string());
+        }
+    }
+    Ok(())
+}
+
+// Every card of the deck exactly once, or twice over for Spider's two decks
+fn check_deck(game: &Game) -> Result<(), PositionError> {
+    let mut unseen: HashMap<(Rank, Suit), usize> = HashMap::new();
+    for card in Game::create_deck(game.rules.variant) {
+        *unseen.entry((card.rank, card.suit)).or_default() += 1;
+    }
+
+    let piles = game
+        .tableau
+        .iter()
+        .chain(&game.foundation)
+        .chain([&game.stock, &game.discard]);
+    for card in piles.flatten() {
+        match unseen.get_mut(&(card.rank, card.suit)) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => {
+                return Err(PositionError::BadCards(format!(
+                    "{} appears too many times",
+                    card
+                )))
+            }
+        }
+    }
+
+    // Each copy still unseen is listed once
+    let missing: Vec<String> = Game::create_deck(game.rules.variant)
+        .into_iter()
+        .filter(|card| match unseen.get_mut(&(card.rank, card.suit)) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                true
+            }
+            _ => false,
+        })
+        .map(|card| card.to_string())
+        .collect();
+    if !missing.is_empty() {
+        return Err(PositionError::BadCards(format!(
+            "missing {}",
+            missing.join(" ")
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,121 @@
+//! Timed log of everything the player did, so a game can be watched again.
+//!
+//! Positions are never stored: any moment of the game is rebuilt by dealing
+//! the same seed again and replaying the log up to it.
+
+use std::fmt;
+
+use crate::game::Game;
+use crate::moves::Move;
+use crate::rules::Rules;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Play(Move),
+    Undo,
+    Redo,
+}
+
+impl Action {
+    /// Carries out the action, returning whether it changed anything.
+    pub fn perform(self, game: &mut Game) -> bool {
+        match self {
+            Action::Play(mv) => game.apply(mv),
+            Action::Undo => game.undo(),
+            Action::Redo => game.redo(),
+        }
+    }
+}
+
+/// An action and when it happened, in milliseconds since the deal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub at: u64,
+    pub action: Action,
+}
+
+/// Written as `<at>:<action>`, e.g. `1200:w>t2` or `3400:undo`.
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.action {
+            Action::Play(mv) => write!(f, "{}:{}", self.at, mv),
+            Action::Undo => write!(f, "{}:undo", self.at),
+            Action::Redo => write!(f, "{}:redo", self.at),
+        }
+    }
+}
+
+impl Entry {
+    /// Reads an entry written by its `Display` form.
+    pub fn parse(text: &str) -> Option<Entry> {
+        let (at, action) = text.split_once(':')?;
+        let action = match action {
+            "undo" => Action::Undo,
+            "redo" => Action::Redo,
+            mv => Action::Play(Move::parse(mv)?),
+        };
+        Some(Entry {
+            at: at.parse().ok()?,
+            action,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MoveLog {
+    seed: u64,
+    rules: Rules,
+    entries: Vec<Entry>,
+}
+
+impl MoveLog {
+    /// An empty log for `game`, which must be freshly dealt from a seed.
+    pub fn new(game: &Game) -> Option<Self> {
+        Some(MoveLog {
+            seed: game.seed?,
+            rules: game.rules,
+            entries: Vec::new(),
+        })
+    }
+
+    /// A log recorded earlier for the deal `seed` under `rules`.
+    pub fn from_entries(seed: u64, rules: Rules, entries: Vec<Entry>) -> Self {
+        MoveLog {
+            seed,
+            rules,
+            entries,
+        }
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Carries out `action` on `game` and logs it if it changed anything.
+    pub fn record(&mut self, game: &mut Game, action: Action, at: u64) -> bool {
+        let done = action.perform(game);
+        if done {
+            self.entries.push(Entry { at, action });
+        }
+        done
+    }
+
+    /// The game as it was after the first `steps` entries.
+    pub fn replay(&self, steps: usize) -> Game {
+        let mut game = Game::new(self.seed, self.rules);
+        for entry in &self.entries[..steps.min(self.entries.len())] {
+            entry.action.perform(&mut game);
+        }
+        game
+    }
+
+    /// Number of entries logged by `at` milliseconds into the game.
+    pub fn steps_at(&self, at: u64) -> usize {
+        self.entries.partition_point(|entry| entry.at <= at)
+    }
+
+    /// Time of the last entry.
+    pub fn duration(&self) -> u64 {
+        self.entries.last().map_or(0, |entry| entry.at)
+    }
+}
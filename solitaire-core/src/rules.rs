@@ -0,0 +1,176 @@
+use std::fmt;
+
+use crate::card::Suit;
+
+/// The solitaire being played.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    Klondike,
+    /// Two decks dealt into ten columns, where runs of one suit from King
+    /// down to Ace leave the board. `suits` is 1, 2 or 4: the fewer suits
+    /// the two decks are made of, the easier the game.
+    Spider {
+        suits: usize,
+    },
+}
+
+impl Variant {
+    pub fn tableau_piles(self) -> usize {
+        match self {
+            Variant::Klondike => 7,
+            Variant::Spider { .. } => 10,
+        }
+    }
+
+    pub fn foundation_piles(self) -> usize {
+        match self {
+            Variant::Klondike => 4,
+            Variant::Spider { .. } => 8,
+        }
+    }
+
+    /// Suits the cards come in. Spider repeats them until the two decks
+    /// hold eight full suits.
+    pub fn suits(self) -> &'static [Suit] {
+        match self {
+            Variant::Spider { suits: 1 } => &[Suit::Spades],
+            Variant::Spider { suits: 2 } => &[Suit::Spades, Suit::Hearts],
+            _ => &Suit::ALL,
+        }
+    }
+
+    /// Reads a variant written by its `Display` form.
+    pub fn parse(text: &str) -> Option<Variant> {
+        match text {
+            "klondike" => Some(Variant::Klondike),
+            "spider1" => Some(Variant::Spider { suits: 1 }),
+            "spider2" => Some(Variant::Spider { suits: 2 }),
+            "spider4" => Some(Variant::Spider { suits: 4 }),
+            _ => None,
+        }
+    }
+}
+
+/// `klondike`, or `spider` followed by the number of suits, e.g. `spider2`.
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Variant::Klondike => write!(f, "klondike"),
+            Variant::Spider { suits } => write!(f, "spider{}", suits),
+        }
+    }
+}
+
+/// How many times the player may go through the stock.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PassLimit {
+    Unlimited,
+    /// Total passes through the deck, so `Passes(1)` allows no recycling.
+    Passes(u32),
+}
+
+impl PassLimit {
+    /// Number of times the discard pile may be turned back into the stock.
+    pub fn max_recycles(self) -> Option<u32> {
+        match self {
+            PassLimit::Unlimited => None,
+            PassLimit::Passes(passes) => Some(passes.saturating_sub(1)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scoring {
+    /// Windows-style points for moves, with a time bonus on win.
+    Standard,
+    /// Each deal costs a buy-in and every card on the foundations pays out.
+    Vegas,
+}
+
+/// Table rules chosen when a game is dealt.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rules {
+    pub variant: Variant,
+    /// Cards turned from the stock per click: 1 or 3. Only Klondike has a
+    /// waste to draw to, as Spider deals a card to every column instead.
+    pub draw_count: usize,
+    pub pass_limit: PassLimit,
+    pub scoring: Scoring,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            variant: Variant::Klondike,
+            draw_count: 1,
+            pass_limit: PassLimit::Unlimited,
+            scoring: Scoring::Standard,
+        }
+    }
+}
+
+impl Rules {
+    /// Vegas rules: a single pass when drawing one card, three passes when
+    /// drawing three.
+    pub fn vegas(draw_count: usize) -> Self {
+        Rules {
+            variant: Variant::Klondike,
+            draw_count,
+            pass_limit: PassLimit::Passes(if draw_count == 3 { 3 } else { 1 }),
+            scoring: Scoring::Vegas,
+        }
+    }
+
+    /// Spider with `suits` suits.
+    pub fn spider(suits: usize) -> Self {
+        Rules {
+            variant: Variant::Spider { suits },
+            ..Rules::default()
+        }
+    }
+
+    /// Reads rules written by their `Display` form. Rules written before
+    /// there were variants have no `variant` and are Klondike. Spider has
+    /// no waste and a score of its own, so its Klondike options may only
+    /// say what `Rules::spider` sets.
+    pub fn parse(text: &str) -> Option<Rules> {
+        let mut rules = Rules::default();
+        for option in text.split_whitespace() {
+            match option.split_once('=')? {
+                ("variant", variant) => rules.variant = Variant::parse(variant)?,
+                ("draw", "1") => rules.draw_count = 1,
+                ("draw", "3") => rules.draw_count = 3,
+                ("passes", "0") => rules.pass_limit = PassLimit::Unlimited,
+                ("passes", passes) => rules.pass_limit = PassLimit::Passes(passes.parse().ok()?),
+                ("scoring", "standard") => rules.scoring = Scoring::Standard,
+                ("scoring", "vegas") => rules.scoring = Scoring::Vegas,
+                _ => return None,
+            }
+        }
+        match rules.variant {
+            Variant::Spider { suits } if rules != Rules::spider(suits) => None,
+            _ => Some(rules),
+        }
+    }
+}
+
+/// Options in the style of the page URL, e.g.
+/// `variant=klondike draw=3 passes=0 scoring=vegas`, where zero passes means
+/// no limit.
+impl fmt::Display for Rules {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let passes = match self.pass_limit {
+            PassLimit::Unlimited => 0,
+            PassLimit::Passes(passes) => passes,
+        };
+        let scoring = match self.scoring {
+            Scoring::Standard => "standard",
+            Scoring::Vegas => "vegas",
+        };
+        write!(
+            f,
+            "variant={} draw={} passes={} scoring={}",
+            self.variant, self.draw_count, passes, scoring
+        )
+    }
+}
@@ -0,0 +1,165 @@
+//! Text form of a game in progress, so a front end can keep it across
+//! page loads.
+//!
+//! Only the deal number, the rules and the moves are stored; replaying the
+//! moves rebuilds the piles, the score and the undo log exactly. A save
+//! looks like:
+//!
+//! ```text
+//! solitaire-save 1
+//! seed 12345
+//! rules draw=3 passes=3 scoring=vegas
+//! time 95
+//! moves w>t2 t6>f0 draw3 t1>t4*2
+//! undone t0>f1
+//! log 1800:w>t2 4100:t6>f0 5000:draw3 7300:t1>t4*2 9000:t0>f1 9600:undo
+//! ```
+//!
+//! `undone` lists the redo stack, next redo first. `log` is the timed
+//! `MoveLog` the game can be replayed from; saves without it get one
+//! rebuilt from the moves, all at time zero.
+
+use crate::game::Game;
+use crate::moves::Move;
+use crate::replay::{Action, Entry};
+use crate::rules::Rules;
+
+const HEADER: &str = "solitaire-save 1";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SavedGame {
+    pub seed: u64,
+    pub rules: Rules,
+    /// Seconds played so far.
+    pub elapsed_seconds: u64,
+    /// Moves made, oldest first, without automatic flips or finished runs.
+    pub moves: Vec<Move>,
+    /// Moves undo has taken back, next redo first, without automatic moves.
+    pub undone: Vec<Move>,
+    /// Everything the player did, for replays.
+    pub log: Vec<Entry>,
+}
+
+impl SavedGame {
+    /// Captures `game`; only games dealt from a seed can be saved.
+    pub fn new(game: &Game, elapsed_seconds: u64, log: &[Entry]) -> Option<Self> {
+        let is_move = |mv: &Move| !mv.is_automatic();
+        Some(SavedGame {
+            seed: game.seed?,
+            rules: game.rules,
+            elapsed_seconds,
+            moves: game
+                .history()
+                .iter()
+                .map(|record| record.mv)
+                .filter(is_move)
+                .collect(),
+            undone: game
+                .undone()
+                .iter()
+                .rev()
+                .copied()
+                .filter(is_move)
+                .collect(),
+            log: log.to_vec(),
+        })
+    }
+
+    /// Deals the game again and replays the moves, or `None` if one of them
+    /// no longer applies.
+    pub fn restore(&self) -> Option<Game> {
+        let mut game = Game::new(self.seed, self.rules);
+        for &mv in &self.moves {
+            if !game.apply(mv) {
+                return None;
+            }
+        }
+
+        // Play the undone moves too and take them back, which rebuilds the
+        // redo stack with their flips
+        for &mv in &self.undone {
+            if !game.apply(mv) {
+                return None;
+            }
+        }
+        for _ in &self.undone {
+            game.undo();
+        }
+
+        Some(game)
+    }
+
+    pub fn encode(&self) -> String {
+        let moves = |moves: &[Move]| {
+            moves
+                .iter()
+                .map(|mv| format!(" {}", mv))
+                .collect::<String>()
+        };
+        let log = self
+            .log
+            .iter()
+            .map(|entry| format!(" {}", entry))
+            .collect::<String>();
+
+        format!(
+            "{}\nseed {}\nrules {}\ntime {}\nmoves{}\nundone{}\nlog{}\n",
+            HEADER,
+            self.seed,
+            self.rules,
+            self.elapsed_seconds,
+            moves(&self.moves),
+            moves(&self.undone),
+            log,
+        )
+    }
+
+    /// Reads a save written by `encode`, or `None` if it is damaged.
+    pub fn decode(text: &str) -> Option<Self> {
+        let mut lines = text.lines();
+        if lines.next()? != HEADER {
+            return None;
+        }
+        let mut field = |name: &str| {
+            let line = lines.next()?;
+            let value = line.strip_prefix(name)?;
+            Some(value.split_whitespace().collect::<Vec<_>>())
+        };
+
+        let seed = field("seed")?.first()?.parse().ok()?;
+        let rules = Rules::parse(&field("rules")?.join(" "))?;
+        let elapsed_seconds = field("time")?.first()?.parse().ok()?;
+        let moves = field("moves")?
+            .into_iter()
+            .map(Move::parse)
+            .collect::<Option<Vec<_>>>()?;
+        let undone = field("undone")?
+            .into_iter()
+            .map(Move::parse)
+            .collect::<Option<Vec<_>>>()?;
+        let log = match field("log") {
+            Some(log) => log.into_iter().map(Entry::parse).collect::<Option<_>>()?,
+            None => rebuilt_log(&moves, &undone),
+        };
+
+        Some(SavedGame {
+            seed,
+            rules,
+            elapsed_seconds,
+            moves,
+            undone,
+            log,
+        })
+    }
+}
+
+// A log that leads to the same game: every move, then the undone ones
+// played and taken back again
+fn rebuilt_log(moves: &[Move], undone: &[Move]) -> Vec<Entry> {
+    let played = moves.iter().chain(undone).map(|&mv| Action::Play(mv));
+    let taken_back = undone.iter().map(|_| Action::Undo);
+    played
+        .chain(taken_back)
+        .map(|action| Entry { at: 0, action })
+        .collect()
+}
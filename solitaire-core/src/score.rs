@@ -0,0 +1,81 @@
+//! Standard (Windows-style) and Vegas Klondike scoring, and the Windows
+//! Spider score.
+
+use crate::game::Game;
+use crate::moves::Move;
+use crate::rules::{Rules, Variant};
+
+pub const VEGAS_BUY_IN: i32 = 52;
+pub const VEGAS_PAYOUT: i32 = 5; // Per card on the foundations
+pub const SPIDER_START: i32 = 500;
+pub const SPIDER_RUN: i32 = 100; // Per run lifted off the board
+
+/// Standard score before the first move: Spider starts high and loses a
+/// point per move.
+pub fn starting_score(rules: &Rules) -> i32 {
+    match rules.variant {
+        Variant::Klondike => 0,
+        Variant::Spider { .. } => SPIDER_START,
+    }
+}
+
+/// Points for a single move. The game keeps the running total from going
+/// below zero.
+pub fn standard_points(mv: Move, rules: &Rules) -> i32 {
+    if let Variant::Spider { .. } = rules.variant {
+        return match mv {
+            Move::CompleteRun { .. } => SPIDER_RUN,
+            Move::Flip { .. } => 0,
+            _ => -1,
+        };
+    }
+    match mv {
+        Move::DiscardToTableau { .. } => 5,
+        Move::DiscardToFoundation { .. } | Move::TableauToFoundation { .. } => 10,
+        Move::Flip { .. } => 5,
+        Move::FoundationToTableau { .. } => -15,
+        Move::Recycle if rules.draw_count == 1 => -100,
+        Move::Recycle => -20,
+        Move::TableauToTableau { .. } | Move::Draw { .. } => 0,
+        Move::Deal | Move::CompleteRun { .. } => 0,
+    }
+}
+
+/// Bonus added to the score of a won game: 700,000 divided by the seconds
+/// it took, only for games lasting at least 30 seconds.
+pub fn time_bonus(seconds: u64) -> i32 {
+    if seconds < 30 {
+        0
+    } else {
+        (700_000 / seconds) as i32
+    }
+}
+
+/// Vegas result of a game so far: the buy-in plus the payout for every
+/// card on the foundations.
+pub fn vegas_score(game: &Game) -> i32 {
+    let cards: usize = game.foundation.iter().map(Vec::len).sum();
+    cards as i32 * VEGAS_PAYOUT - VEGAS_BUY_IN
+}
+
+/// Cumulative Vegas: the bank carries over from one game to the next.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VegasBank {
+    carried: i32, // Balance before the current game
+}
+
+impl VegasBank {
+    pub fn new(balance: i32) -> Self {
+        VegasBank { carried: balance }
+    }
+
+    /// Balance including the buy-in and payouts of the game in progress.
+    pub fn balance(&self, game: &Game) -> i32 {
+        self.carried + vegas_score(game)
+    }
+
+    /// Closes `game` so its result carries into the next one.
+    pub fn settle(&mut self, game: &Game) {
+        self.carried = self.balance(game);
+    }
+}
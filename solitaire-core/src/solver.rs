@@ -0,0 +1,409 @@
+//! Depth-first Klondike solver.
+//!
+//! The solver sees the whole position, face-down cards included, so it
+//! answers whether the deal can be won at all, not whether a player would
+//! find the win. It only prunes moves that can never be needed (safe
+//! foundation moves, shuffling a whole column between empty piles and
+//! positions already searched), so running out of moves is a proof that
+//! the position is lost. Spider is not searched; its hints come from the
+//! same move ordering alone.
+
+use std::collections::HashSet;
+
+use crate::card::{Card, Rank, Suit};
+use crate::game::{Game, Pile};
+use crate::moves::Move;
+use crate::rng::SplitMix64;
+use crate::rules::{PassLimit, Variant};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Solution {
+    /// Moves that win from the position, in order. Automatic flips are left
+    /// out since `Game::apply` performs them.
+    Win(Vec<Move>),
+    /// Every line was searched without finding a win.
+    Unwinnable,
+    /// The node budget ran out before the search finished.
+    Unknown,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Solver {
+    /// Maximum number of moves tried before giving up.
+    pub node_budget: usize,
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        Solver {
+            node_budget: 200_000,
+        }
+    }
+}
+
+// Steps still to try from one position on the search path
+struct Frame {
+    steps: Vec<Vec<Move>>,
+    next: usize,
+}
+
+impl Solver {
+    pub fn new(node_budget: usize) -> Self {
+        Solver { node_budget }
+    }
+
+    /// Looks for a win with a quick search that skips rarely useful moves,
+    /// then spends the rest of the budget on a complete search that can
+    /// also prove the position lost. Spider positions are always
+    /// `Unknown`.
+    pub fn solve(&self, game: &Game) -> Solution {
+        if game.rules.variant != Variant::Klondike {
+            return Solution::Unknown;
+        }
+
+        // Quick searches easily get stuck below one bad early move, so they
+        // restart with growing budgets and a different order of equally
+        // promising moves each time
+        let mut quick_budget = self.node_budget / 2;
+        let mut rng = SplitMix64::new(0);
+        let mut attempt_budget = 500;
+        while quick_budget > 0 {
+            let budget = attempt_budget.min(quick_budget);
+            if let Solution::Win(moves) = search(game, budget, Some(&mut rng)) {
+                return Solution::Win(moves);
+            }
+            quick_budget -= budget;
+            attempt_budget *= 2;
+        }
+        search(game, self.node_budget - self.node_budget / 2, None)
+    }
+
+    /// Move to suggest to a player: the first move of a win when one is
+    /// found, otherwise the most promising legal move.
+    pub fn hint(&self, game: &Game) -> Option<Move> {
+        if let Solution::Win(moves) = self.solve(game) {
+            return moves.first().copied();
+        }
+        game.legal_moves()
+            .into_iter()
+            .filter(|&mv| !is_pointless(game, mv) && !is_speculative(game, mv))
+            .min_by_key(|&mv| priority(game, mv))
+    }
+}
+
+// Depth-first search over positions. Given a random generator to shuffle
+// the move order it also skips moves that are only occasionally needed,
+// so its failures prove nothing.
+fn search(game: &Game, node_budget: usize, mut rng: Option<&mut SplitMix64>) -> Solution {
+    let mut game = game.clone();
+    if game.check_game_won() {
+        return Solution::Win(Vec::new());
+    }
+
+    let mut seen = HashSet::new();
+    seen.insert(position_key(&game));
+
+    let mut path: Vec<Vec<Move>> = Vec::new();
+    let mut stack = vec![Frame {
+        steps: candidate_steps(&game, rng.as_deref_mut()),
+        next: 0,
+    }];
+    let mut nodes = 0;
+
+    while let Some(frame) = stack.last_mut() {
+        let Some(step) = frame.steps.get(frame.next).cloned() else {
+            // Dead end: step back to the previous position
+            stack.pop();
+            if let Some(step) = path.pop() {
+                take_back(&mut game, &step);
+            }
+            continue;
+        };
+        frame.next += 1;
+
+        nodes += 1;
+        if nodes > node_budget {
+            return Solution::Unknown;
+        }
+
+        for &mv in &step {
+            let applied = game.apply(mv);
+            debug_assert!(applied, "solver generated an illegal move");
+        }
+        if game.check_game_won() {
+            path.push(step);
+            return Solution::Win(path.concat());
+        }
+        if !seen.insert(position_key(&game)) {
+            take_back(&mut game, &step);
+            continue;
+        }
+
+        path.push(step);
+        stack.push(Frame {
+            steps: candidate_steps(&game, rng.as_deref_mut()),
+            next: 0,
+        });
+    }
+
+    if rng.is_none() {
+        Solution::Unwinnable
+    } else {
+        Solution::Unknown
+    }
+}
+
+// Every applied move is undone by one undo, which also takes back its flip
+fn take_back(game: &mut Game, step: &[Move]) {
+    for _ in step {
+        game.undo();
+    }
+}
+
+// Search steps worth trying, most promising first. A step is a single
+// move, or draws from the stock followed by playing the card they turned
+// up; drawing is only ever useful to play that card, so bare draws are
+// never tried on their own.
+fn candidate_steps(game: &Game, rng: Option<&mut SplitMix64>) -> Vec<Vec<Move>> {
+    let complete = rng.is_none();
+    let moves = game.legal_moves();
+
+    // A safe foundation move never hurts, so it is the only one worth trying
+    if let Some(&safe) = moves.iter().find(|&&mv| is_safe_foundation_move(game, mv)) {
+        return vec![vec![safe]];
+    }
+
+    let mut steps: Vec<Vec<Move>> = moves
+        .into_iter()
+        .filter(|&mv| !matches!(mv, Move::Draw { .. } | Move::Recycle))
+        .filter(|&mv| !is_pointless(game, mv))
+        .filter(|&mv| complete || !is_speculative(game, mv))
+        .map(|mv| vec![mv])
+        .collect();
+    steps.extend(stock_steps(game));
+
+    match rng {
+        Some(rng) => {
+            // Mixes moves of equal or neighbouring priority
+            steps.sort_by_cached_key(|step| {
+                priority(game, *step.last().unwrap()) as u64 * 2 + rng.below(4)
+            });
+        }
+        None => steps.sort_by_key(|step| (priority(game, *step.last().unwrap()), step.len())),
+    }
+    steps
+}
+
+// Draws (and recycles) that bring each reachable stock card to the top of
+// the discard pile, followed by every way of playing it
+fn stock_steps(game: &Game) -> Vec<Vec<Move>> {
+    let mut stock = game.stock.clone();
+    let mut discard = game.discard.clone();
+    let mut recycles_left = game.recycles_left();
+    let mut recycled = 0;
+    let mut draws = Vec::new();
+    let mut reached = HashSet::new();
+    let mut steps = Vec::new();
+
+    // After a second recycle the stock only repeats itself
+    while recycled < 2 {
+        if !stock.is_empty() {
+            let count = stock.len().min(game.rules.draw_count);
+            for _ in 0..count {
+                discard.push(stock.pop().unwrap());
+            }
+            draws.push(Move::Draw { count });
+        } else if !discard.is_empty() && recycles_left != Some(0) {
+            stock.extend(discard.drain(..).rev());
+            recycles_left = recycles_left.map(|left| left - 1);
+            recycled += 1;
+            draws.push(Move::Recycle);
+            continue;
+        } else {
+            break;
+        }
+
+        let card = *discard.last().unwrap();
+        if !reached.insert(card) {
+            continue;
+        }
+        for play in discard_plays(game, &card) {
+            let mut step = draws.clone();
+            step.push(play);
+            steps.push(step);
+        }
+    }
+
+    steps
+}
+
+// Moves that would play `card` from the top of the discard pile
+fn discard_plays(game: &Game, card: &Card) -> Vec<Move> {
+    let mut plays = Vec::new();
+
+    let foundation = game.foundation.iter().position(|pile| match pile.last() {
+        Some(target) => Game::is_valid_foundation_move(card, target),
+        None => card.rank == Rank::Ace,
+    });
+    if let Some(to) = foundation {
+        plays.push(Move::DiscardToFoundation { to });
+    }
+
+    let first_empty = game.tableau.iter().position(Vec::is_empty);
+    for (to, pile) in game.tableau.iter().enumerate() {
+        let fits = match pile.last() {
+            Some(target) => target.face_up && Game::is_valid_tableau_move(card, target),
+            // Empty columns are interchangeable
+            None => card.rank == Rank::King && Some(to) == first_empty,
+        };
+        if fits {
+            plays.push(Move::DiscardToTableau { to });
+        }
+    }
+
+    plays
+}
+
+// Moving a whole column into an empty one changes nothing, and any empty
+// column is as good as the first one. A King that is the last card of the
+// waste still has to be played out of it.
+fn is_pointless(game: &Game, mv: Move) -> bool {
+    let first_empty = game.tableau.iter().position(Vec::is_empty);
+    match mv.transfer() {
+        Some((from, count, Pile::Tableau(to))) if game.tableau[to].is_empty() => {
+            let whole_column = matches!(from, Pile::Tableau(_)) && count == game.pile(from).len();
+            Some(to) != first_empty || whole_column
+        }
+        _ => false,
+    }
+}
+
+// Taking cards back off the foundations, or splitting a run without
+// sending the card it uncovers up, is rarely part of a win
+fn is_speculative(game: &Game, mv: Move) -> bool {
+    match mv {
+        Move::FoundationToTableau { .. } => true,
+        Move::TableauToTableau { from, count, .. } => {
+            let pile = &game.tableau[from];
+            let rest = pile.len() - count;
+            rest > 0 && pile[rest - 1].face_up && {
+                let exposed = &pile[rest - 1];
+                !game
+                    .foundation
+                    .iter()
+                    .any(|foundation| match foundation.last() {
+                        Some(target) => Game::is_valid_foundation_move(exposed, target),
+                        None => exposed.rank == Rank::Ace,
+                    })
+            }
+        }
+        _ => false,
+    }
+}
+
+// Lower sorts first
+fn priority(game: &Game, mv: Move) -> u8 {
+    match mv {
+        Move::TableauToFoundation { .. } | Move::DiscardToFoundation { .. } => 0,
+        Move::Flip { .. } | Move::CompleteRun { .. } => 0,
+        Move::TableauToTableau { from, count, .. } => {
+            let pile = &game.tableau[from];
+            match pile.len() - count {
+                // Empties the column
+                0 => 3,
+                // Uncovers a face-down card
+                rest if !pile[rest - 1].face_up => 1,
+                // Only splits a run
+                _ => 5,
+            }
+        }
+        Move::DiscardToTableau { .. } => 2,
+        Move::Draw { .. } | Move::Recycle | Move::Deal => 4,
+        Move::FoundationToTableau { .. } => 6,
+    }
+}
+
+// Rank showing on the foundation for each suit, 0 when empty
+fn foundation_ranks(game: &Game) -> [u8; 4] {
+    let mut ranks = [0; 4];
+    for card in game.foundation.iter().filter_map(|pile| pile.last()) {
+        ranks[card.suit.index()] = card.rank.ordinal();
+    }
+    ranks
+}
+
+// A card may go up for good once no card that could still want to sit on
+// it, or be sat on by a card that needs it, is left off the foundations
+fn is_safe_foundation_move(game: &Game, mv: Move) -> bool {
+    let card = match mv.transfer() {
+        Some((from, _, Pile::Foundation(_))) => *game.pile(from).last().unwrap(),
+        _ => return false,
+    };
+    let rank = card.rank.ordinal();
+    if rank <= 2 {
+        return true;
+    }
+
+    let ranks = foundation_ranks(game);
+    Suit::ALL.iter().all(|&suit| {
+        let needed = if suit.color() != card.color() {
+            rank - 1
+        } else if suit != card.suit {
+            rank - 2
+        } else {
+            0
+        };
+        ranks[suit.index()] >= needed
+    })
+}
+
+fn card_byte(card: &Card) -> u8 {
+    let id = card.suit.index() as u8 * 13 + card.rank.ordinal() - 1;
+    if card.face_up {
+        id | 0x80
+    } else {
+        id
+    }
+}
+
+// Identifies positions that play the same: tableau columns are
+// interchangeable and so are foundation piles of different suits
+fn position_key(game: &Game) -> Vec<u8> {
+    let mut columns: Vec<Vec<u8>> = game
+        .tableau
+        .iter()
+        .map(|pile| pile.iter().map(card_byte).collect())
+        .collect();
+    columns.sort();
+
+    let mut key = Vec::with_capacity(80);
+    for column in columns {
+        key.extend(column);
+        key.push(0xFF);
+    }
+    key.extend(foundation_ranks(game));
+
+    match game.rules.pass_limit {
+        // Drawing one card at a time with no pass limit, any card of the
+        // stock can be turned up without changing the order of the rest, so
+        // only that order matters and not how far through it the player is
+        PassLimit::Unlimited if game.rules.draw_count == 1 => {
+            let waste = game.discard.iter().chain(game.stock.iter().rev());
+            key.extend(waste.map(|card| card_byte(card) & 0x7F));
+        }
+        PassLimit::Unlimited => {
+            key.extend(game.stock.iter().map(card_byte));
+            key.push(0xFF);
+            key.extend(game.discard.iter().map(card_byte));
+        }
+        // Used passes only matter while they are limited
+        PassLimit::Passes(_) => {
+            key.extend(game.stock.iter().map(card_byte));
+            key.push(0xFF);
+            key.extend(game.discard.iter().map(card_byte));
+            key.push(0xFF);
+            key.push(game.recycles() as u8);
+        }
+    }
+    key
+}
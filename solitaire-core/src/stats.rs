@@ -0,0 +1,168 @@
+//! Lifetime statistics, kept separately for each variant and its draw mode
+//! or number of suits.
+//!
+//! Stored as one line per mode, e.g.
+//!
+//! ```text
+//! klondike-draw1 played=12 won=5 streak=2 longest=3 time=184 score=5120 moves=97
+//! spider-suits1 played=3 won=1 streak=0 longest=1 time=611 score=1104 moves=294
+//! ```
+//!
+//! where `time`, `score` and `moves` are the best results and only appear
+//! once a game of that mode has been won.
+
+use std::collections::BTreeMap;
+
+use crate::game::Game;
+use crate::rules::{Rules, Scoring, Variant};
+
+/// Results of every game played in one mode.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Tally {
+    pub played: u32,
+    pub won: u32,
+    /// Wins in a row, up to the last game.
+    pub streak: u32,
+    pub longest_streak: u32,
+    /// Fastest win, in seconds.
+    pub best_time: Option<u64>,
+    /// Highest final score of a win, time bonus included. Vegas games have
+    /// no score here since their money goes to the bank.
+    pub best_score: Option<i32>,
+    /// Fewest moves in a win, automatic flips and finished runs not counted.
+    pub fewest_moves: Option<usize>,
+}
+
+impl Tally {
+    /// Share of games won, in percent.
+    pub fn win_rate(&self) -> f64 {
+        if self.played == 0 {
+            0.0
+        } else {
+            100.0 * self.won as f64 / self.played as f64
+        }
+    }
+
+    fn encode(&self) -> String {
+        let mut text = format!(
+            "played={} won={} streak={} longest={}",
+            self.played, self.won, self.streak, self.longest_streak
+        );
+        if let Some(time) = self.best_time {
+            text += &format!(" time={}", time);
+        }
+        if let Some(score) = self.best_score {
+            text += &format!(" score={}", score);
+        }
+        if let Some(moves) = self.fewest_moves {
+            text += &format!(" moves={}", moves);
+        }
+        text
+    }
+
+    fn decode(text: &str) -> Option<Tally> {
+        let mut tally = Tally::default();
+        for field in text.split_whitespace() {
+            match field.split_once('=')? {
+                ("played", value) => tally.played = value.parse().ok()?,
+                ("won", value) => tally.won = value.parse().ok()?,
+                ("streak", value) => tally.streak = value.parse().ok()?,
+                ("longest", value) => tally.longest_streak = value.parse().ok()?,
+                ("time", value) => tally.best_time = Some(value.parse().ok()?),
+                ("score", value) => tally.best_score = Some(value.parse().ok()?),
+                ("moves", value) => tally.fewest_moves = Some(value.parse().ok()?),
+                _ => return None,
+            }
+        }
+        Some(tally)
+    }
+}
+
+/// Key the statistics of games under `rules` are kept under.
+pub fn mode(rules: &Rules) -> String {
+    match rules.variant {
+        Variant::Klondike => format!("klondike-draw{}", rules.draw_count),
+        Variant::Spider { suits } => format!("spider-suits{}", suits),
+    }
+}
+
+/// Name of a mode for display, e.g. "Klondike, draw 3" or "Spider, 2 suits".
+pub fn mode_label(mode: &str) -> String {
+    let (variant, detail) = if let Some((variant, draw)) = mode.split_once("-draw") {
+        (variant, format!("draw {}", draw))
+    } else if let Some((variant, suits)) = mode.split_once("-suits") {
+        let noun = if suits == "1" { "suit" } else { "suits" };
+        (variant, format!("{} {}", suits, noun))
+    } else {
+        (mode, "?".to_string())
+    };
+    let mut letters = variant.chars();
+    let variant: String = match letters.next() {
+        Some(first) => first.to_uppercase().chain(letters).collect(),
+        None => String::new(),
+    };
+    format!("{}, {}", variant, detail)
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Statistics {
+    tallies: BTreeMap<String, Tally>,
+}
+
+impl Statistics {
+    /// Modes with at least one game recorded, in a stable order.
+    pub fn modes(&self) -> impl Iterator<Item = &str> {
+        self.tallies.keys().map(String::as_str)
+    }
+
+    pub fn tally(&self, mode: &str) -> Tally {
+        self.tallies.get(mode).copied().unwrap_or_default()
+    }
+
+    /// Counts `game` as won, taking `seconds` to play and scoring
+    /// `final_score` with any time bonus.
+    pub fn record_win(&mut self, game: &Game, seconds: u64, final_score: i32) {
+        let moves = game
+            .history()
+            .iter()
+            .filter(|record| !record.mv.is_automatic())
+            .count();
+        let tally = self.tallies.entry(mode(&game.rules)).or_default();
+        tally.played += 1;
+        tally.won += 1;
+        tally.streak += 1;
+        tally.longest_streak = tally.longest_streak.max(tally.streak);
+        tally.best_time = Some(tally.best_time.map_or(seconds, |best| best.min(seconds)));
+        tally.fewest_moves = Some(tally.fewest_moves.map_or(moves, |best| best.min(moves)));
+        if game.rules.scoring == Scoring::Standard {
+            let best = tally
+                .best_score
+                .map_or(final_score, |best| best.max(final_score));
+            tally.best_score = Some(best);
+        }
+    }
+
+    /// Counts a game under `rules` that was given up.
+    pub fn record_loss(&mut self, rules: &Rules) {
+        let tally = self.tallies.entry(mode(rules)).or_default();
+        tally.played += 1;
+        tally.streak = 0;
+    }
+
+    pub fn encode(&self) -> String {
+        self.tallies
+            .iter()
+            .map(|(mode, tally)| format!("{} {}\n", mode, tally.encode()))
+            .collect()
+    }
+
+    /// Reads statistics written by `encode`, or `None` if they are damaged.
+    pub fn decode(text: &str) -> Option<Self> {
+        let mut tallies = BTreeMap::new();
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            let (mode, tally) = line.split_once(' ').unwrap_or((line, ""));
+            tallies.insert(mode.to_string(), Tally::decode(tally)?);
+        }
+        Some(Statistics { tallies })
+    }
+}
@@ -0,0 +1,66 @@
+//! Ranks, suits and the card helpers built on them.
+
+use solitaire_core::{Card, Color, Game, Rank, Suit, Variant};
+
+#[test]
+fn suits_know_their_colour_index_and_name() {
+    let colors: Vec<Color> = Suit::ALL.iter().map(|suit| suit.color()).collect();
+    assert_eq!(colors, [Color::Red, Color::Red, Color::Black, Color::Black]);
+    for (i, suit) in Suit::ALL.into_iter().enumerate() {
+        assert_eq!(suit.index(), i);
+    }
+    let names: Vec<&str> = Suit::ALL.iter().map(|suit| suit.name()).collect();
+    assert_eq!(names, ["hearts", "diamonds", "clubs", "spades"]);
+}
+
+#[test]
+fn ranks_count_from_one_to_thirteen() {
+    for (i, rank) in Rank::ALL.into_iter().enumerate() {
+        assert_eq!(rank.ordinal() as usize, i + 1);
+        assert_eq!(Rank::from_ordinal(rank.ordinal()), Some(rank));
+    }
+    assert_eq!(Rank::from_ordinal(0), None);
+    assert_eq!(Rank::from_ordinal(14), None);
+}
+
+#[test]
+fn ranks_stop_at_the_ace_and_the_king() {
+    assert_eq!(Rank::Ace.predecessor(), None);
+    assert_eq!(Rank::Ace.successor(), Some(Rank::Two));
+    assert_eq!(Rank::Ten.successor(), Some(Rank::Jack));
+    assert_eq!(Rank::King.predecessor(), Some(Rank::Queen));
+    assert_eq!(Rank::King.successor(), None);
+}
+
+#[test]
+fn rank_labels_match_the_sprites() {
+    let labels: Vec<&str> = Rank::ALL.iter().map(|rank| rank.label()).collect();
+    assert_eq!(
+        labels,
+        ["A", "2", "3", "4", "5", "6", "7", "8", "9", "10", "J", "Q", "K"]
+    );
+}
+
+#[test]
+fn cards_are_dealt_face_down_in_their_suit_colour() {
+    let card = Card::new(Rank::Queen, Suit::Diamonds);
+    assert!(!card.face_up);
+    assert_eq!(card.color(), Color::Red);
+    assert!(card.is_red());
+    assert!(!Card::new(Rank::Queen, Suit::Spades).is_red());
+}
+
+#[test]
+fn cards_read_back_from_their_codes() {
+    let ten = Card::new(Rank::Ten, Suit::Spades);
+    assert_eq!(ten.to_string(), "TS");
+    assert_eq!(Card::parse("TS"), Some(ten));
+    assert_eq!(Card::parse("10s"), Some(ten));
+    assert_eq!(Card::parse("AH"), Some(Card::new(Rank::Ace, Suit::Hearts)));
+    assert_eq!(Card::parse("1H"), None);
+    assert_eq!(Card::parse("KX"), None);
+    assert_eq!(Card::parse(""), None);
+    for card in Game::create_deck(Variant::Klondike) {
+        assert_eq!(Card::parse(&card.to_string()), Some(card));
+    }
+}
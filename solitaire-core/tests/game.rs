@@ -0,0 +1,174 @@
+//! The Klondike deal, the stock and the rules for moving cards, played
+//! without a front end.
+
+use solitaire_core::{Card, Game, Move, Pile, Rank, Rules, Suit, Variant};
+
+fn card(rank: Rank, suit: Suit) -> Card {
+    Card {
+        face_up: true,
+        ..Card::new(rank, suit)
+    }
+}
+
+// Whether the top `count` cards of `from` may go onto `to`
+fn legal(game: &Game, from: Pile, count: usize, to: Pile) -> bool {
+    Move::between(from, count, to).is_some_and(|mv| game.is_legal(mv))
+}
+
+fn play(game: &mut Game, from: Pile, count: usize, to: Pile) -> bool {
+    game.apply(Move::between(from, count, to).unwrap())
+}
+
+// A board with every pile empty, for placing just the cards a test needs
+fn empty_board() -> Game {
+    let mut game = Game::deal(Game::create_deck(Variant::Klondike), Rules::default());
+    game.tableau = vec![Vec::new(); 7];
+    game.stock.clear();
+    game
+}
+
+#[test]
+fn the_deck_holds_every_card_once() {
+    let deck = Game::create_deck(Variant::Klondike);
+    assert_eq!(deck.len(), 52);
+    for (i, card) in deck.iter().enumerate() {
+        assert!(!deck[..i].contains(card), "{:?} appears twice", card);
+    }
+}
+
+#[test]
+fn spider_decks_repeat_their_suits_to_eight() {
+    for suits in [1, 2, 4] {
+        let deck = Game::create_deck(Variant::Spider { suits });
+        assert_eq!(deck.len(), 104);
+        for card in &deck {
+            let copies = deck.iter().filter(|other| *other == card).count();
+            assert_eq!(copies, 8 / suits, "{:?} with {} suits", card, suits);
+        }
+    }
+}
+
+#[test]
+fn the_deal_fans_out_seven_piles() {
+    let game = Game::deal(Game::create_deck(Variant::Klondike), Rules::default());
+    for (i, pile) in game.tableau.iter().enumerate() {
+        assert_eq!(pile.len(), i + 1);
+        let face_up: Vec<bool> = pile.iter().map(|card| card.face_up).collect();
+        assert_eq!(face_up.iter().filter(|&&up| up).count(), 1);
+        assert!(face_up[i]);
+    }
+    assert_eq!(game.stock.len(), 24);
+    assert!(game.stock.iter().all(|card| !card.face_up));
+    assert!(game.discard.is_empty());
+    assert!(game.foundation.iter().all(Vec::is_empty));
+}
+
+#[test]
+fn the_stock_turns_over_and_recycles() {
+    let mut game = empty_board();
+    game.stock = vec![
+        Card::new(Rank::Two, Suit::Clubs),
+        Card::new(Rank::Nine, Suit::Hearts),
+    ];
+
+    let draw = game.stock_move().unwrap();
+    assert_eq!(draw, Move::Draw { count: 1 });
+    assert!(game.apply(draw));
+    assert_eq!(game.discard, [card(Rank::Nine, Suit::Hearts)]);
+    assert!(game.apply(draw));
+    assert!(game.stock.is_empty());
+    assert!(!game.is_legal(draw));
+
+    // The waste goes back in the order it was drawn
+    assert_eq!(game.stock_move(), Some(Move::Recycle));
+    assert!(game.apply(Move::Recycle));
+    assert_eq!(
+        game.stock,
+        [
+            Card::new(Rank::Two, Suit::Clubs),
+            Card::new(Rank::Nine, Suit::Hearts)
+        ]
+    );
+    assert!(game.discard.is_empty());
+
+    game.stock.clear();
+    assert_eq!(game.stock_move(), None);
+    assert!(!game.is_legal(Move::Recycle));
+}
+
+#[test]
+fn tableau_cards_go_down_in_alternating_colours() {
+    let mut game = empty_board();
+    game.tableau[0] = vec![card(Rank::Eight, Suit::Spades)];
+    game.tableau[1] = vec![card(Rank::Seven, Suit::Hearts)];
+    game.tableau[2] = vec![card(Rank::Seven, Suit::Clubs)];
+    game.tableau[3] = vec![card(Rank::Six, Suit::Diamonds)];
+
+    assert!(legal(&game, Pile::Tableau(1), 1, Pile::Tableau(0)));
+    assert!(!legal(&game, Pile::Tableau(2), 1, Pile::Tableau(0)));
+    assert!(!legal(&game, Pile::Tableau(3), 1, Pile::Tableau(0)));
+    assert!(!legal(&game, Pile::Tableau(0), 1, Pile::Tableau(1)));
+
+    // Empty piles take Kings only
+    assert!(!legal(&game, Pile::Tableau(0), 1, Pile::Tableau(4)));
+    game.tableau[5] = vec![card(Rank::King, Suit::Hearts)];
+    assert!(legal(&game, Pile::Tableau(5), 1, Pile::Tableau(4)));
+}
+
+#[test]
+fn foundations_build_up_in_suit_from_the_ace() {
+    let mut game = empty_board();
+    game.tableau[0] = vec![card(Rank::Two, Suit::Hearts)];
+    game.tableau[1] = vec![card(Rank::Ace, Suit::Hearts)];
+    game.discard = vec![card(Rank::Two, Suit::Diamonds)];
+
+    assert!(!legal(&game, Pile::Tableau(0), 1, Pile::Foundation(0)));
+    assert!(play(&mut game, Pile::Tableau(1), 1, Pile::Foundation(0)));
+    assert!(!legal(&game, Pile::Discard, 1, Pile::Foundation(0)));
+    assert!(play(&mut game, Pile::Tableau(0), 1, Pile::Foundation(0)));
+    assert_eq!(
+        game.foundation[0],
+        [card(Rank::Ace, Suit::Hearts), card(Rank::Two, Suit::Hearts)]
+    );
+
+    // Foundation cards may come back to the tableau, but not move across
+    game.tableau[2] = vec![card(Rank::Three, Suit::Clubs)];
+    assert!(legal(&game, Pile::Foundation(0), 1, Pile::Tableau(2)));
+    assert!(!legal(&game, Pile::Foundation(0), 1, Pile::Foundation(1)));
+}
+
+#[test]
+fn runs_move_whole_and_uncover_the_card_beneath() {
+    let mut game = empty_board();
+    game.tableau[0] = vec![
+        Card::new(Rank::Four, Suit::Spades),
+        card(Rank::Nine, Suit::Clubs),
+        card(Rank::Eight, Suit::Hearts),
+        card(Rank::Seven, Suit::Spades),
+    ];
+    game.tableau[1] = vec![card(Rank::Ten, Suit::Diamonds)];
+
+    // Reaching under to the face-down card, or a run the Ten does not take
+    assert!(!legal(&game, Pile::Tableau(0), 4, Pile::Tableau(1)));
+    assert!(!legal(&game, Pile::Tableau(0), 2, Pile::Tableau(1)));
+
+    assert!(play(&mut game, Pile::Tableau(0), 3, Pile::Tableau(1)));
+    assert_eq!(game.tableau[0], [card(Rank::Four, Suit::Spades)]);
+    assert_eq!(
+        game.history().last().map(|record| record.mv),
+        Some(Move::Flip { pile: 0 })
+    );
+    assert_eq!(game.tableau[1].len(), 4);
+}
+
+#[test]
+fn a_game_is_won_with_every_card_on_the_foundations() {
+    let mut game = empty_board();
+    assert!(!game.check_game_won());
+
+    let deck = Game::create_deck(Variant::Klondike);
+    for (pile, suit) in game.foundation.iter_mut().zip(deck.chunks(13)) {
+        *pile = suit.iter().map(|c| card(c.rank, c.suit)).collect();
+    }
+    assert!(game.check_game_won());
+}
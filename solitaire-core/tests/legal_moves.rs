@@ -0,0 +1,123 @@
+//! `Game::legal_moves`, which hints and bots build on.
+
+use solitaire_core::{Card, Game, Move, PassLimit, Rank, Rules, SplitMix64, Suit, Variant};
+
+fn card(rank: Rank, suit: Suit) -> Card {
+    Card {
+        face_up: true,
+        ..Card::new(rank, suit)
+    }
+}
+
+// A board with every pile empty, for placing just the cards a test needs
+fn empty_board(rules: Rules) -> Game {
+    let mut game = Game::deal(Game::create_deck(Variant::Klondike), rules);
+    game.tableau = vec![Vec::new(); 7];
+    game.stock.clear();
+    game
+}
+
+fn stock_clicks(game: &Game) -> Vec<Move> {
+    game.legal_moves()
+        .into_iter()
+        .filter(|mv| matches!(mv, Move::Draw { .. } | Move::Recycle))
+        .collect()
+}
+
+#[test]
+fn every_part_of_a_run_can_move() {
+    let mut game = empty_board(Rules::default());
+    game.tableau[0] = vec![
+        card(Rank::King, Suit::Spades),
+        card(Rank::Queen, Suit::Hearts),
+        card(Rank::Jack, Suit::Clubs),
+        card(Rank::Ten, Suit::Diamonds),
+    ];
+    game.tableau[2] = vec![card(Rank::Jack, Suit::Spades)];
+    game.tableau[3] = vec![card(Rank::Queen, Suit::Diamonds)];
+    game.tableau[4] = vec![card(Rank::King, Suit::Clubs)];
+    let moves = game.legal_moves();
+
+    for expected in [
+        Move::TableauToTableau {
+            from: 0,
+            to: 2,
+            count: 1,
+        },
+        Move::TableauToTableau {
+            from: 0,
+            to: 3,
+            count: 2,
+        },
+        Move::TableauToTableau {
+            from: 0,
+            to: 4,
+            count: 3,
+        },
+        Move::TableauToTableau {
+            from: 0,
+            to: 1,
+            count: 4,
+        },
+    ] {
+        assert!(moves.contains(&expected), "{:?} is missing", expected);
+    }
+}
+
+#[test]
+fn the_stock_click_appears_exactly_when_it_is_legal() {
+    let mut game = empty_board(Rules::default());
+    game.stock = vec![Card::new(Rank::King, Suit::Spades)];
+    assert_eq!(stock_clicks(&game), [Move::Draw { count: 1 }]);
+
+    game.discard = std::mem::take(&mut game.stock);
+    assert_eq!(stock_clicks(&game), [Move::Recycle]);
+
+    // The only pass is used up
+    game.rules.pass_limit = PassLimit::Passes(1);
+    assert_eq!(game.stock_move(), None);
+    assert_eq!(stock_clicks(&game), []);
+
+    // Nothing left to draw or recycle
+    let game = empty_board(Rules::default());
+    assert_eq!(game.stock_move(), None);
+    assert_eq!(stock_clicks(&game), []);
+}
+
+#[test]
+fn every_listed_move_is_legal() {
+    for draw_count in [1, 3] {
+        let rules = Rules {
+            draw_count,
+            ..Rules::default()
+        };
+        for seed in 0..20 {
+            let mut game = Game::new(seed, rules);
+            let mut rng = SplitMix64::new(seed);
+            for _ in 0..200 {
+                let moves = game.legal_moves();
+                if moves.is_empty() {
+                    break;
+                }
+                for &mv in &moves {
+                    assert!(game.is_legal(mv), "{:?} is listed but not legal", mv);
+                }
+                let mv = moves[rng.below(moves.len() as u64) as usize];
+                assert!(game.apply(mv));
+            }
+        }
+    }
+}
+
+#[test]
+fn piles_past_the_board_are_never_legal() {
+    let game = Game::new(1, Rules::default());
+    assert!(!game.is_legal(Move::Flip { pile: 12 }));
+    assert!(!game.is_legal(Move::TableauToTableau {
+        from: 9,
+        to: 0,
+        count: 1
+    }));
+    assert!(!game.is_legal(Move::TableauToFoundation { from: 0, to: 4 }));
+    assert!(!game.is_legal(Move::CompleteRun { from: 10, to: 8 }));
+}
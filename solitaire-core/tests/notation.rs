@@ -0,0 +1,94 @@
+//! Deals in the PySol-style solver notation: read back exactly, from any
+//! casing of the cards, and refused when malformed.
+
+use solitaire_core::notation::{self, NotationError};
+use solitaire_core::{Game, Rules};
+
+const DEAL: &str = "\
+Foundations: H-0 D-0 C-0 S-0
+Talon: 8H KH 6S 3S 9D 7S QS AH 2D TH 9H 2H TS 6C 7C JC 9S 5D 6D 6H 9C 4S KS 8S
+: JH
+: JS AD
+: 3H QC KC
+: JD 3D 5H 4C
+: AS 5C 4H AC 7D
+: 7H QH 4D 2C 3C KD
+: 5S QD TD 8C 8D 2S TC
+";
+
+fn assert_same_board(a: &Game, b: &Game) {
+    assert_eq!(a.tableau, b.tableau);
+    assert_eq!(a.foundation, b.foundation);
+    assert_eq!(a.stock, b.stock);
+    assert_eq!(a.discard, b.discard);
+}
+
+#[test]
+fn seeded_deals_round_trip() {
+    for seed in 0..100 {
+        let game = Game::new(seed, Rules::default());
+        let text = notation::export_deal(&game).unwrap();
+        let imported = notation::import_deal(&text, Rules::default()).unwrap();
+        assert_same_board(&imported, &game);
+        assert_eq!(notation::export_deal(&imported).unwrap(), text);
+    }
+}
+
+#[test]
+fn listing_round_trips() {
+    let game = notation::import_deal(DEAL, Rules::default()).unwrap();
+    assert_same_board(&game, &Game::new(5, Rules::default()));
+    assert_eq!(notation::export_deal(&game).unwrap(), DEAL);
+}
+
+#[test]
+fn games_in_progress_export_their_deal() {
+    let mut game = Game::new(5, Rules::default());
+    let mv = game.stock_move().unwrap();
+    game.apply(mv);
+    assert_eq!(notation::export_deal(&game).unwrap(), DEAL);
+}
+
+#[test]
+fn accepts_lower_case_and_ten_written_out() {
+    let text = DEAL.replace("TC", "10c").replace("JH", "jh");
+    let game = notation::import_deal(&text, Rules::default()).unwrap();
+    assert_eq!(notation::export_deal(&game).unwrap(), DEAL);
+}
+
+#[test]
+fn rejects_malformed_deals() {
+    let import = |text: &str| notation::import_deal(text, Rules::default()).unwrap_err();
+
+    assert_eq!(
+        import(&DEAL.replace("QC", "QX")),
+        NotationError::BadCard {
+            line: 5,
+            code: "QX".to_string()
+        }
+    );
+    assert_eq!(
+        import(&DEAL.replace(": JH\n", "")),
+        NotationError::TableauPiles(6)
+    );
+    assert_eq!(
+        import(&DEAL.replace(": JS AD", ": JS")),
+        NotationError::PileSize { pile: 1, len: 1 }
+    );
+    assert_eq!(
+        import(&DEAL.replace(" KS 8S", " KS")),
+        NotationError::TalonSize(23)
+    );
+    assert_eq!(
+        import(&DEAL.replace("H-0", "H-A")),
+        NotationError::Foundations
+    );
+    assert!(matches!(
+        import(&DEAL.replace("AD", "JH")),
+        NotationError::Duplicate(_)
+    ));
+    assert_eq!(
+        import(&format!("{}Waste: 3C\n", DEAL)),
+        NotationError::UnknownLine(10)
+    );
+}
@@ -0,0 +1,212 @@
+//! The `position` text format: what goes in comes back out, and every way
+//! text can be wrong is reported as such.
+
+use solitaire_core::position::{self, PositionError};
+use solitaire_core::{Card, Game, Move, PassLimit, Rules, Scoring, Variant};
+
+// Deal 9 drawing three, with the stock drawn through, recycled once and
+// drawn again, and the first tableau move made
+fn mid_game() -> Game {
+    let mut game = Game::new(9, Rules::parse("draw=3 passes=0 scoring=standard").unwrap());
+    while !game.stock.is_empty() {
+        assert!(game.apply(game.stock_move().unwrap()));
+    }
+    assert!(game.apply(Move::Recycle));
+    assert!(game.apply(game.stock_move().unwrap()));
+    if let Some(&mv) = game.legal_moves().iter().find(|mv| mv.transfer().is_some()) {
+        assert!(game.apply(mv));
+    }
+    game
+}
+
+// The text of `mid_game` with `line` (1-based) replaced, or left out if
+// `replacement` is empty
+fn with_line(line: usize, replacement: &str) -> String {
+    position::encode(&mid_game())
+        .lines()
+        .enumerate()
+        .map(|(i, text)| if i + 1 == line { replacement } else { text })
+        .filter(|text| !text.is_empty())
+        .map(|text| format!("{}\n", text))
+        .collect()
+}
+
+fn error(text: &str) -> PositionError {
+    position::decode(text).unwrap_err()
+}
+
+fn bad_line(text: &str) -> (usize, String) {
+    match error(text) {
+        PositionError::BadLine { line, reason } => (line, reason),
+        other => panic!("expected a bad line, got {:?}", other),
+    }
+}
+
+// A one-suit Spider position with `column` on t0 and `stock` in the stock
+fn spider_text(column: &[Card], stock: &[Card]) -> String {
+    let codes =
+        |cards: &[Card]| -> String { cards.iter().map(|card| format!(" {}", card)).collect() };
+    let mut text = format!(
+        "solitaire-position 2\nrules {}\nscore 500\nrecycles 0\ns{}\nw\n",
+        Rules::spider(1),
+        codes(stock)
+    );
+    for i in 0..8 {
+        text += &format!("f{}\n", i);
+    }
+    text += &format!("t0{}\n", codes(column));
+    for i in 1..10 {
+        text += &format!("t{}\n", i);
+    }
+    text
+}
+
+#[test]
+fn positions_round_trip_mid_game() {
+    let game = mid_game();
+    assert_eq!(game.recycles(), 1);
+    assert!(game.tableau.iter().flatten().any(|card| !card.face_up));
+
+    let decoded = position::decode(&position::encode(&game)).unwrap();
+    assert_eq!(decoded.tableau, game.tableau);
+    assert_eq!(decoded.foundation, game.foundation);
+    assert_eq!(decoded.stock, game.stock);
+    assert_eq!(decoded.discard, game.discard);
+    assert_eq!(decoded.rules, game.rules);
+    assert_eq!(decoded.score(), game.score());
+    assert_eq!(decoded.recycles(), game.recycles());
+}
+
+#[test]
+fn other_text_has_no_header() {
+    let text = position::encode(&mid_game()).replacen("solitaire-position", "solitaire-save", 1);
+    assert_eq!(error(&text), PositionError::MissingHeader);
+    assert_eq!(error(""), PositionError::MissingHeader);
+}
+
+#[test]
+fn newer_versions_are_refused() {
+    let text = with_line(1, "solitaire-position 9");
+    assert_eq!(error(&text), PositionError::UnsupportedVersion(9));
+}
+
+#[test]
+fn keys_appear_once() {
+    let text = position::encode(&mid_game()) + "score 0\n";
+    assert_eq!(bad_line(&text), (18, "\"score\" appears twice".to_string()));
+}
+
+#[test]
+fn unknown_keys_are_refused() {
+    let text = position::encode(&mid_game()) + "t7 KS\n";
+    assert_eq!(bad_line(&text), (18, "unknown key \"t7\"".to_string()));
+}
+
+#[test]
+fn missing_keys_are_named() {
+    assert_eq!(
+        error(&with_line(3, "")),
+        PositionError::MissingKey("score".to_string())
+    );
+}
+
+#[test]
+fn cards_must_parse() {
+    let text = with_line(6, "w 1X");
+    assert_eq!(bad_line(&text), (6, "\"1X\" is not a card".to_string()));
+}
+
+#[test]
+fn every_card_appears_once() {
+    let game = mid_game();
+    let last = *game.tableau[0].last().unwrap();
+
+    // The waste line holds its cards again, as well as the card on t0
+    let waste: String = game
+        .discard
+        .iter()
+        .map(|card| format!(" {}", card))
+        .collect();
+    let text = with_line(6, &format!("w{} {}", waste, last));
+    assert_eq!(
+        error(&text),
+        PositionError::BadCards(format!("{} appears too many times", last))
+    );
+
+    let text = with_line(11, "t0");
+    assert_eq!(
+        error(&text),
+        PositionError::BadCards(format!("missing {}", last))
+    );
+}
+
+#[test]
+fn face_down_top_cards_are_turned_over() {
+    let game = mid_game();
+    let cards: String = game.tableau[0]
+        .iter()
+        .map(|card| format!(" {}", card))
+        .collect();
+    let decoded = position::decode(&with_line(11, &format!("t0{} |", cards))).unwrap();
+
+    let (top, rest) = decoded.tableau[0].split_last().unwrap();
+    assert!(top.face_up);
+    assert!(rest.iter().all(|card| !card.face_up));
+    assert!(!decoded.is_legal(Move::Flip { pile: 0 }));
+}
+
+#[test]
+fn finished_runs_cannot_stay_on_a_column() {
+    let deck = Game::create_deck(Variant::Spider { suits: 1 });
+    let run: Vec<Card> = deck[..13].iter().rev().copied().collect();
+    assert_eq!(
+        error(&spider_text(&run, &deck[13..])),
+        PositionError::BadCards("t0 ends in a finished run".to_string())
+    );
+
+    // Without its Ace the run is an ordinary column
+    let stock = [&deck[..1], &deck[13..]].concat();
+    assert!(position::decode(&spider_text(&run[..12], &stock)).is_ok());
+}
+
+#[test]
+fn rules_read_back_from_their_options() {
+    let rules = Rules {
+        draw_count: 3,
+        pass_limit: PassLimit::Passes(2),
+        scoring: Scoring::Vegas,
+        ..Rules::default()
+    };
+    assert_eq!(
+        rules.to_string(),
+        "variant=klondike draw=3 passes=2 scoring=vegas"
+    );
+    assert_eq!(Rules::parse(&rules.to_string()), Some(rules));
+    assert_eq!(Rules::parse(""), Some(Rules::default()));
+    assert_eq!(Rules::parse("draw=2"), None);
+    assert_eq!(Rules::parse("scoring"), None);
+
+    // Spider takes no Klondike options but their defaults
+    let spider = Rules::spider(2);
+    assert_eq!(Rules::parse(&spider.to_string()), Some(spider));
+    for klondike_only in ["scoring=vegas", "draw=3", "passes=2"] {
+        let text = format!("variant=spider2 {}", klondike_only);
+        assert_eq!(Rules::parse(&text), None, "{:?}", text);
+    }
+}
+
+#[test]
+fn spider_has_no_waste() {
+    let game = Game::new(9, Rules::spider(2));
+    let (last, rest) = game.stock.split_last().unwrap();
+    let stock: String = rest.iter().map(|card| format!(" {}", card)).collect();
+    let text = position::encode(&game)
+        .lines()
+        .map(|line| match line.split_whitespace().next() {
+            Some("s") => format!("s{}\n", stock),
+            Some("w") => format!("w {}\n", last),
+            _ => format!("{}\n", line),
+        })
+        .collect::<String>();
+    assert_eq!(bad_line(&text), (6, "Spider has no waste".to_string()));
+}
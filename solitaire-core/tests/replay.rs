@@ -0,0 +1,105 @@
+//! Timed move logs: their text form and the games rebuilt from them.
+
+use solitaire_core::replay::{Action, Entry, MoveLog};
+use solitaire_core::{position, Game, Move, Rules};
+
+#[test]
+fn entries_round_trip_through_text() {
+    let entries = [
+        (
+            Entry {
+                at: 1200,
+                action: Action::Play(Move::DiscardToTableau { to: 2 }),
+            },
+            "1200:w>t2",
+        ),
+        (
+            Entry {
+                at: 3400,
+                action: Action::Undo,
+            },
+            "3400:undo",
+        ),
+        (
+            Entry {
+                at: 5600,
+                action: Action::Redo,
+            },
+            "5600:redo",
+        ),
+    ];
+    for (entry, text) in entries {
+        assert_eq!(entry.to_string(), text);
+        assert_eq!(Entry::parse(text), Some(entry));
+    }
+
+    for text in ["", "1200", "soon:undo", "1200:sideways", "-5:redo"] {
+        assert_eq!(Entry::parse(text), None, "{:?}", text);
+    }
+}
+
+#[test]
+fn replays_rebuild_the_live_game() {
+    let mut game = Game::new(21, Rules::default());
+    let mut log = MoveLog::new(&game).unwrap();
+    let mut positions = vec![position::encode(&game)];
+    let mut at = 0;
+    let mut record = |game: &mut Game, action: Action| {
+        at += 1000;
+        assert!(log.record(game, action, at));
+        positions.push(position::encode(game));
+    };
+
+    // A few moves, with undo and redo among them
+    for _ in 0..6 {
+        let mv = game
+            .legal_moves()
+            .into_iter()
+            .find(|mv| mv.transfer().is_some())
+            .or_else(|| game.stock_move())
+            .unwrap();
+        record(&mut game, Action::Play(mv));
+    }
+    record(&mut game, Action::Undo);
+    record(&mut game, Action::Undo);
+    record(&mut game, Action::Redo);
+    let draw = game.stock_move().unwrap();
+    record(&mut game, Action::Play(draw));
+
+    assert_eq!(log.entries().len(), 10);
+    for (steps, expected) in positions.iter().enumerate() {
+        assert_eq!(&position::encode(&log.replay(steps)), expected);
+    }
+    assert_eq!(position::encode(&log.replay(99)), position::encode(&game));
+}
+
+#[test]
+fn refused_actions_are_not_logged() {
+    let mut game = Game::new(21, Rules::default());
+    let mut log = MoveLog::new(&game).unwrap();
+    assert!(!log.record(&mut game, Action::Undo, 500));
+    assert!(!log.record(&mut game, Action::Play(Move::Recycle), 900));
+    assert!(log.entries().is_empty());
+    assert_eq!(log.duration(), 0);
+}
+
+#[test]
+fn steps_and_duration_follow_the_clock() {
+    let entries = [1000, 2500, 2500, 4000]
+        .into_iter()
+        .map(|at| Entry {
+            at,
+            action: Action::Undo,
+        })
+        .collect();
+    let log = MoveLog::from_entries(21, Rules::default(), entries);
+
+    assert_eq!(log.steps_at(0), 0);
+    assert_eq!(log.steps_at(999), 0);
+    assert_eq!(log.steps_at(1000), 1);
+    assert_eq!(log.steps_at(2499), 1);
+    assert_eq!(log.steps_at(2500), 3);
+    assert_eq!(log.steps_at(4000), 4);
+    assert_eq!(log.steps_at(u64::MAX), 4);
+    assert_eq!(log.duration(), 4000);
+}
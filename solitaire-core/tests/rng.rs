@@ -0,0 +1,47 @@
+//! Deal numbers are shared between players, so the generator's output is
+//! pinned: a change here changes every deal.
+
+use solitaire_core::{Game, Rules, SplitMix64, Variant};
+
+#[test]
+fn first_outputs_are_pinned() {
+    // The published SplitMix64 sequence for seed 0
+    let mut rng = SplitMix64::new(0);
+    let outputs: Vec<u64> = (0..3).map(|_| rng.next_u64()).collect();
+    assert_eq!(
+        outputs,
+        [
+            0xE220_A839_7B1D_CDAF,
+            0x6E78_9E6A_A1B9_65F4,
+            0x06C4_5D18_8009_454F
+        ]
+    );
+}
+
+#[test]
+fn shuffles_are_pinned() {
+    let mut items: Vec<u32> = (0..10).collect();
+    SplitMix64::new(12345).shuffle(&mut items);
+    assert_eq!(items, [8, 6, 7, 2, 1, 3, 9, 5, 0, 4]);
+}
+
+#[test]
+#[should_panic]
+fn below_zero_is_refused() {
+    SplitMix64::new(1).below(0);
+}
+
+#[test]
+fn a_deal_number_gives_the_same_deal_every_time() {
+    let first = Game::new(2024, Rules::default());
+    let again = Game::new(2024, Rules::default());
+    assert_eq!(first.tableau, again.tableau);
+    assert_eq!(first.stock, again.stock);
+    assert_eq!(first.seed, Some(2024));
+
+    assert_ne!(Game::new(2025, Rules::default()).stock, first.stock);
+    assert_eq!(
+        Game::deal(Game::create_deck(Variant::Klondike), Rules::default()).seed,
+        None
+    );
+}
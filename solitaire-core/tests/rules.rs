@@ -0,0 +1,525 @@
+//! Klondike and Spider rules at their edges. Every case starts from a
+//! position in the `position` text format, plays one move and checks the
+//! position after it, or checks that the move is refused and changes
+//! nothing.
+
+use solitaire_core::{position, Game, Move, Pile};
+
+const ACE_ON_WASTE: &str = "\
+solitaire-position 1
+rules draw=1 passes=0 scoring=standard
+score 0
+recycles 0
+s AH 3H 4H 5H 6H 7H 8H 9H TH JH QH KH AD 2D 3D 4D 5D 6D 7D 8D 9D TD JD QD KD AC 2C 3C 4C 5C 6C 7C 8C 9C TC JC QC KC 2S 3S 4S 5S 6S 7S 8S 9S TS JS QS KS
+w 2H AS
+f0
+f1
+f2
+f3
+t0
+t1
+t2
+t3
+t4
+t5
+t6
+";
+
+const ACE_ON_FOUNDATION: &str = "\
+solitaire-position 1
+rules draw=1 passes=0 scoring=standard
+score 10
+recycles 0
+s AH 3H 4H 5H 6H 7H 8H 9H TH JH QH KH AD 2D 3D 4D 5D 6D 7D 8D 9D TD JD QD KD AC 2C 3C 4C 5C 6C 7C 8C 9C TC JC QC KC 2S 3S 4S 5S 6S 7S 8S 9S TS JS QS KS
+w 2H
+f0 AS
+f1
+f2
+f3
+t0
+t1
+t2
+t3
+t4
+t5
+t6
+";
+
+const TWO_ON_WASTE: &str = "\
+solitaire-position 1
+rules draw=1 passes=0 scoring=standard
+score 0
+recycles 0
+s AH 3H 4H 5H 6H 7H 8H 9H TH JH QH KH AD 2D 3D 4D 5D 6D 7D 8D 9D TD JD QD KD AC 2C 3C 4C 5C 6C 7C 8C 9C TC JC QC KC 3S 4S 5S 6S 7S 8S 9S TS JS QS KS
+w AS 2H
+f0
+f1
+f2
+f3
+t0 2S
+t1
+t2
+t3
+t4
+t5
+t6
+";
+
+const EMPTY_COLUMN: &str = "\
+solitaire-position 1
+rules draw=1 passes=0 scoring=standard
+score 0
+recycles 0
+s AH 2H 3H 4H 5H 6H 7H 8H 9H TH JH KH AD 2D 3D 4D 5D 6D 7D 8D 9D TD JD QD AC 2C 3C 4C 5C 6C 7C 8C 9C TC JC KC AS 2S 3S 4S 5S 6S 7S 8S 9S TS JS QS
+w KD
+f0
+f1
+f2
+f3
+t0
+t1 KS QH
+t2 QC
+t3
+t4
+t5
+t6
+";
+
+const KING_RUN_MOVED: &str = "\
+solitaire-position 1
+rules draw=1 passes=0 scoring=standard
+score 0
+recycles 0
+s AH 2H 3H 4H 5H 6H 7H 8H 9H TH JH KH AD 2D 3D 4D 5D 6D 7D 8D 9D TD JD QD AC 2C 3C 4C 5C 6C 7C 8C 9C TC JC KC AS 2S 3S 4S 5S 6S 7S 8S 9S TS JS QS
+w KD
+f0
+f1
+f2
+f3
+t0 KS QH
+t1
+t2 QC
+t3
+t4
+t5
+t6
+";
+
+const KING_FROM_WASTE: &str = "\
+solitaire-position 1
+rules draw=1 passes=0 scoring=standard
+score 5
+recycles 0
+s AH 2H 3H 4H 5H 6H 7H 8H 9H TH JH KH AD 2D 3D 4D 5D 6D 7D 8D 9D TD JD QD AC 2C 3C 4C 5C 6C 7C 8C 9C TC JC KC AS 2S 3S 4S 5S 6S 7S 8S 9S TS JS QS
+w
+f0
+f1
+f2
+f3
+t0 KD
+t1 KS QH
+t2 QC
+t3
+t4
+t5
+t6
+";
+
+const FOUNDATIONS_STARTED: &str = "\
+solitaire-position 1
+rules draw=1 passes=0 scoring=standard
+score 50
+recycles 0
+s 4H 5H 6H 7H 8H 9H TH JH QH KH AD 2D 3D 4D 5D 6D 7D 8D 9D TD JD QD KD AC 2C 3C 4C 5C 6C 7C 8C 9C TC JC QC KC 3S 5S 6S 7S 8S 9S TS JS QS KS
+w
+f0 AH 2H 3H
+f1
+f2 AS 2S
+f3
+t0
+t1 4S
+t2
+t3
+t4
+t5
+t6
+";
+
+const FOUNDATION_CARD_BACK: &str = "\
+solitaire-position 1
+rules draw=1 passes=0 scoring=standard
+score 35
+recycles 0
+s 4H 5H 6H 7H 8H 9H TH JH QH KH AD 2D 3D 4D 5D 6D 7D 8D 9D TD JD QD KD AC 2C 3C 4C 5C 6C 7C 8C 9C TC JC QC KC 3S 5S 6S 7S 8S 9S TS JS QS KS
+w
+f0 AH 2H
+f1
+f2 AS 2S
+f3
+t0
+t1 4S 3H
+t2
+t3
+t4
+t5
+t6
+";
+
+const RUNS: &str = "\
+solitaire-position 1
+rules draw=1 passes=0 scoring=standard
+score 0
+recycles 0
+s AH 2H 3H 4H 5H 7H JH QH KH AD 2D 4D 5D 6D 9D TD JD QD KD AC 2C 3C 4C 5C 6C 8C TC JC QC AS 2S 3S 4S 5S 6S 7S 8S JS QS KS
+w
+f0
+f1
+f2
+f3
+t0 KC | 9H 8H
+t1 TS
+t2 3D | 9C 8D 7C
+t3 TH
+t4 6H | 9S 7D
+t5
+t6
+";
+
+const RUN_MOVED: &str = "\
+solitaire-position 1
+rules draw=1 passes=0 scoring=standard
+score 5
+recycles 0
+s AH 2H 3H 4H 5H 7H JH QH KH AD 2D 4D 5D 6D 9D TD JD QD KD AC 2C 3C 4C 5C 6C 8C TC JC QC AS 2S 3S 4S 5S 6S 7S 8S JS QS KS
+w
+f0
+f1
+f2
+f3
+t0 KC | 9H 8H
+t1 TS
+t2 3D
+t3 TH 9C 8D 7C
+t4 6H | 9S 7D
+t5
+t6
+";
+
+const NOTHING_TO_RECYCLE: &str = "\
+solitaire-position 1
+rules draw=1 passes=0 scoring=standard
+score 0
+recycles 0
+s
+w
+f0 AH
+f1
+f2
+f3
+t0
+t1
+t2
+t3
+t4
+t5
+t6 2H 3H 4H 5H 6H 7H 8H 9H TH JH QH KH AD 2D 3D 4D 5D 6D 7D 8D 9D TD JD QD KD AC 2C 3C 4C 5C 6C 7C 8C 9C TC JC QC KC AS 2S 3S 4S 5S 6S 7S 8S 9S TS JS QS | KS
+";
+
+const WASTE_TO_RECYCLE: &str = "\
+solitaire-position 1
+rules draw=1 passes=0 scoring=standard
+score 100
+recycles 0
+s
+w 4C 7D 9S
+f0
+f1
+f2
+f3
+t0
+t1
+t2
+t3
+t4
+t5
+t6 AH 2H 3H 4H 5H 6H 7H 8H 9H TH JH QH KH AD 2D 3D 4D 5D 6D 8D 9D TD JD QD KD AC 2C 3C 5C 6C 7C 8C 9C TC JC QC KC AS 2S 3S 4S 5S 6S 7S 8S TS JS QS | KS
+";
+
+const RECYCLED: &str = "\
+solitaire-position 1
+rules draw=1 passes=0 scoring=standard
+score 0
+recycles 1
+s 9S 7D 4C
+w
+f0
+f1
+f2
+f3
+t0
+t1
+t2
+t3
+t4
+t5
+t6 AH 2H 3H 4H 5H 6H 7H 8H 9H TH JH QH KH AD 2D 3D 4D 5D 6D 8D 9D TD JD QD KD AC 2C 3C 5C 6C 7C 8C 9C TC JC QC KC AS 2S 3S 4S 5S 6S 7S 8S TS JS QS | KS
+";
+
+const LAST_PASS: &str = "\
+solitaire-position 1
+rules draw=1 passes=1 scoring=standard
+score 0
+recycles 0
+s
+w 4C 7D 9S
+f0
+f1
+f2
+f3
+t0
+t1
+t2
+t3
+t4
+t5
+t6 AH 2H 3H 4H 5H 6H 7H 8H 9H TH JH QH KH AD 2D 3D 4D 5D 6D 8D 9D TD JD QD KD AC 2C 3C 5C 6C 7C 8C 9C TC JC QC KC AS 2S 3S 4S 5S 6S 7S 8S TS JS QS | KS
+";
+
+const CARD_TO_UNCOVER: &str = "\
+solitaire-position 1
+rules draw=1 passes=0 scoring=standard
+score 0
+recycles 0
+s AH 2H 3H 4H 5H 6H 7H 8H TH JH QH KH AD 2D 3D 4D 5D 6D 7D 8D 9D TD QD KD AC 2C 3C 4C 6C 7C 8C 9C TC JC QC KC AS 2S 3S 4S 5S 6S 7S 8S 9S JS QS KS
+w
+f0
+f1
+f2
+f3
+t0 5C | 9H
+t1 TS
+t2 JD
+t3
+t4
+t5
+t6
+";
+
+const CARD_UNCOVERED: &str = "\
+solitaire-position 1
+rules draw=1 passes=0 scoring=standard
+score 5
+recycles 0
+s AH 2H 3H 4H 5H 6H 7H 8H TH JH QH KH AD 2D 3D 4D 5D 6D 7D 8D 9D TD QD KD AC 2C 3C 4C 6C 7C 8C 9C TC JC QC KC AS 2S 3S 4S 5S 6S 7S 8S 9S JS QS KS
+w
+f0
+f1
+f2
+f3
+t0 5C
+t1 TS 9H
+t2 JD
+t3
+t4
+t5
+t6
+";
+
+const RUN_READY: &str = "\
+solitaire-position 2
+rules variant=spider1 draw=1 passes=0 scoring=standard
+score 500
+recycles 0
+s AS AS AS AS AS AS AS 2S 2S 2S 2S 2S 2S 3S 3S 3S 3S 3S 4S 4S 4S 4S 4S 5S 5S 5S 5S 5S 6S 6S 6S 6S 6S 7S 7S 7S 7S 7S 8S 8S 8S 8S 8S 9S 9S 9S 9S 9S TS TS TS TS TS TS JS JS JS JS JS JS QS QS QS QS QS QS KS KS KS KS KS KS JS QS KS 8S 7S 6S 5S 4S 3S 9S
+w
+f0
+f1
+f2
+f3
+f4
+f5
+f6
+f7
+t0 KS QS JS TS 9S 8S 7S 6S 5S 4S 3S 2S
+t1 5S | AS
+t2 2S
+t3 3S
+t4 4S
+t5 6S
+t6 7S
+t7 8S
+t8 9S
+t9 TS
+";
+
+const RUN_LIFTED: &str = "\
+solitaire-position 2
+rules variant=spider1 draw=1 passes=0 scoring=standard
+score 599
+recycles 0
+s AS AS AS AS AS AS AS 2S 2S 2S 2S 2S 2S 3S 3S 3S 3S 3S 4S 4S 4S 4S 4S 5S 5S 5S 5S 5S 6S 6S 6S 6S 6S 7S 7S 7S 7S 7S 8S 8S 8S 8S 8S 9S 9S 9S 9S 9S TS TS TS TS TS TS JS JS JS JS JS JS QS QS QS QS QS QS KS KS KS KS KS KS JS QS KS 8S 7S 6S 5S 4S 3S 9S
+w
+f0 KS QS JS TS 9S 8S 7S 6S 5S 4S 3S 2S AS
+f1
+f2
+f3
+f4
+f5
+f6
+f7
+t0
+t1 5S
+t2 2S
+t3 3S
+t4 4S
+t5 6S
+t6 7S
+t7 8S
+t8 9S
+t9 TS
+";
+
+const ROW_DEALT: &str = "\
+solitaire-position 2
+rules variant=spider1 draw=1 passes=0 scoring=standard
+score 499
+recycles 0
+s AS AS AS AS AS AS AS 2S 2S 2S 2S 2S 2S 3S 3S 3S 3S 3S 4S 4S 4S 4S 4S 5S 5S 5S 5S 5S 6S 6S 6S 6S 6S 7S 7S 7S 7S 7S 8S 8S 8S 8S 8S 9S 9S 9S 9S 9S TS TS TS TS TS TS JS JS JS JS JS JS QS QS QS QS QS QS KS KS KS KS KS KS
+w
+f0
+f1
+f2
+f3
+f4
+f5
+f6
+f7
+t0 KS QS JS TS 9S 8S 7S 6S 5S 4S 3S 2S 9S
+t1 5S | AS 3S
+t2 2S 4S
+t3 3S 5S
+t4 4S 6S
+t5 6S 7S
+t6 7S 8S
+t7 8S KS
+t8 9S QS
+t9 TS JS
+";
+
+// Plays `mv` on `before` and checks the result is `after`
+fn assert_transition(before: &str, mv: &str, after: &str) -> Game {
+    let mut game = position::decode(before).unwrap();
+    let mv = Move::parse(mv).unwrap();
+    assert!(game.apply(mv), "{} was refused", mv);
+    let expected = position::decode(after).unwrap();
+    assert_eq!(position::encode(&game), position::encode(&expected));
+    game
+}
+
+// Checks `mv` is refused on `before` and leaves the position as it was
+fn assert_rejected(before: &str, mv: &str) {
+    let mut game = position::decode(before).unwrap();
+    let mv = Move::parse(mv).unwrap();
+    assert!(!game.is_legal(mv), "{} was allowed", mv);
+    assert!(!game.apply(mv));
+    assert_eq!(
+        position::encode(&game),
+        position::encode(&position::decode(before).unwrap())
+    );
+}
+
+#[test]
+fn only_aces_start_a_foundation() {
+    assert_transition(ACE_ON_WASTE, "w>f0", ACE_ON_FOUNDATION);
+    assert_rejected(TWO_ON_WASTE, "w>f0");
+    assert_rejected(TWO_ON_WASTE, "t0>f1");
+}
+
+#[test]
+fn only_kings_fill_an_empty_column() {
+    assert_transition(EMPTY_COLUMN, "t1>t0*2", KING_RUN_MOVED);
+    assert_transition(EMPTY_COLUMN, "w>t0", KING_FROM_WASTE);
+    assert_rejected(EMPTY_COLUMN, "t2>t0");
+    assert_rejected(EMPTY_COLUMN, "t1>t0");
+}
+
+#[test]
+fn foundation_cards_never_move_to_another_foundation() {
+    // There is no such move at all, so nothing can play one
+    assert_eq!(Move::parse("f0>f1"), None);
+    assert_eq!(Move::parse("f2>f0"), None);
+    for from in 0..4 {
+        for to in 0..4 {
+            let mv = Move::between(Pile::Foundation(from), 1, Pile::Foundation(to));
+            assert_eq!(mv, None);
+        }
+    }
+}
+
+#[test]
+fn only_the_top_foundation_card_moves() {
+    assert_transition(FOUNDATIONS_STARTED, "f0>t1", FOUNDATION_CARD_BACK);
+    assert_eq!(Move::parse("f0>t1*2"), None);
+    assert_eq!(
+        Move::between(Pile::Foundation(0), 3, Pile::Tableau(1)),
+        None
+    );
+    // The top card still has to fit where it goes
+    assert_rejected(FOUNDATIONS_STARTED, "f2>t1");
+}
+
+#[test]
+fn only_valid_runs_move_together() {
+    assert_transition(RUNS, "t2>t3*3", RUN_MOVED);
+    // Same colour twice
+    assert_rejected(RUNS, "t0>t1*2");
+    // A rank missing from the run
+    assert_rejected(RUNS, "t4>t3*2");
+    // Reaching under the face-down card
+    assert_rejected(RUNS, "t2>t3*4");
+    // More cards than the pile holds
+    assert_rejected(RUNS, "t1>t3*5");
+    // A valid run on a card it does not follow
+    assert_rejected(RUNS, "t2>t1*3");
+}
+
+#[test]
+fn recycling_needs_a_waste_and_a_pass_left() {
+    assert_rejected(NOTHING_TO_RECYCLE, "recycle");
+    assert_rejected(NOTHING_TO_RECYCLE, "draw1");
+    assert_transition(WASTE_TO_RECYCLE, "recycle", RECYCLED);
+    assert_rejected(LAST_PASS, "recycle");
+}
+
+#[test]
+fn uncovered_cards_flip_and_undo_turns_them_back() {
+    let mut game = assert_transition(CARD_TO_UNCOVER, "t0>t1", CARD_UNCOVERED);
+    assert_eq!(game.history().last().unwrap().mv, Move::Flip { pile: 0 });
+
+    assert!(game.undo());
+    let before = position::decode(CARD_TO_UNCOVER).unwrap();
+    assert_eq!(position::encode(&game), position::encode(&before));
+    assert!(!game.can_undo());
+}
+
+#[test]
+fn spider_lifts_a_finished_run_and_undo_puts_it_back() {
+    let mut game = assert_transition(RUN_READY, "t1>t0", RUN_LIFTED);
+    let automatic: Vec<Move> = game.history()[1..].iter().map(|record| record.mv).collect();
+    assert_eq!(
+        automatic,
+        [Move::Flip { pile: 1 }, Move::CompleteRun { from: 0, to: 0 }]
+    );
+
+    assert!(game.undo());
+    let before = position::decode(RUN_READY).unwrap();
+    assert_eq!(position::encode(&game), position::encode(&before));
+    assert!(!game.can_undo());
+}
+
+#[test]
+fn spider_deals_a_row_only_onto_filled_columns() {
+    assert_transition(RUN_READY, "deal", ROW_DEALT);
+    assert_rejected(RUN_LIFTED, "deal");
+    assert_rejected(RUN_READY, "draw1");
+}
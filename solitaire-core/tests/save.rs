@@ -0,0 +1,154 @@
+//! Saves of games in progress, read back and replayed.
+
+use solitaire_core::replay::{Action, MoveLog};
+use solitaire_core::{Card, Game, Move, Rules, SavedGame, Variant};
+
+type Board = (Vec<Vec<Card>>, Vec<Vec<Card>>, Vec<Card>, Vec<Card>, i32);
+
+fn board(game: &Game) -> Board {
+    (
+        game.tableau.clone(),
+        game.foundation.clone(),
+        game.stock.clone(),
+        game.discard.clone(),
+        game.score(),
+    )
+}
+
+// A game with one move made and taken back, where the move turned a card
+// over
+fn game_with_undone_flip() -> Game {
+    for seed in 0.. {
+        let mut game = Game::new(seed, Rules::default());
+        for mv in game.legal_moves() {
+            game.apply(mv);
+            if matches!(game.history().last(), Some(record) if matches!(record.mv, Move::Flip { .. }))
+            {
+                game.undo();
+                return game;
+            }
+            game.undo();
+        }
+    }
+    unreachable!()
+}
+
+#[test]
+fn moves_read_back_from_their_notation() {
+    for mv in [
+        Move::Draw { count: 3 },
+        Move::Recycle,
+        Move::Flip { pile: 4 },
+        Move::TableauToTableau {
+            from: 3,
+            to: 5,
+            count: 2,
+        },
+        Move::DiscardToFoundation { to: 0 },
+        Move::FoundationToTableau { from: 1, to: 6 },
+    ] {
+        assert_eq!(Move::parse(&mv.to_string()), Some(mv));
+    }
+    assert_eq!(
+        Move::TableauToTableau {
+            from: 3,
+            to: 5,
+            count: 2
+        }
+        .to_string(),
+        "t3>t5*2"
+    );
+    assert_eq!(Move::parse("w>s"), None);
+    assert_eq!(Move::parse("draw"), None);
+}
+
+#[test]
+fn saves_round_trip_with_the_redo_stack_and_its_flips() {
+    let game = game_with_undone_flip();
+    assert!(matches!(game.undone(), [Move::Flip { .. }, _]));
+
+    let saved = SavedGame::new(&game, 42, &[]).unwrap();
+    assert_eq!(saved.undone.len(), 1);
+    let decoded = SavedGame::decode(&saved.encode()).unwrap();
+    assert_eq!(decoded, saved);
+
+    let mut restored = decoded.restore().unwrap();
+    assert_eq!(board(&restored), board(&game));
+    assert_eq!(restored.undone(), game.undone());
+
+    let mut original = game.clone();
+    assert!(restored.redo());
+    assert!(original.redo());
+    assert_eq!(board(&restored), board(&original));
+}
+
+#[test]
+fn saves_without_a_log_get_one_rebuilt() {
+    let game = game_with_undone_flip();
+    let saved = SavedGame::new(&game, 42, &[]).unwrap();
+    let text: String = saved
+        .encode()
+        .lines()
+        .filter(|line| !line.starts_with("log"))
+        .map(|line| format!("{}\n", line))
+        .collect();
+
+    let decoded = SavedGame::decode(&text).unwrap();
+    let actions: Vec<Action> = decoded.log.iter().map(|entry| entry.action).collect();
+    let expected: Vec<Action> = saved
+        .moves
+        .iter()
+        .chain(&saved.undone)
+        .map(|&mv| Action::Play(mv))
+        .chain([Action::Undo])
+        .collect();
+    assert_eq!(actions, expected);
+
+    let log = MoveLog::from_entries(decoded.seed, decoded.rules, decoded.log.clone());
+    let replayed = log.replay(decoded.log.len());
+    assert_eq!(board(&replayed), board(&game));
+}
+
+#[test]
+fn moves_that_no_longer_apply_do_not_restore() {
+    let game = game_with_undone_flip();
+    let mut saved = SavedGame::new(&game, 42, &[]).unwrap();
+    // The stock still holds cards, so there is nothing to recycle
+    saved.moves.push(Move::Recycle);
+    assert!(SavedGame::decode(&saved.encode())
+        .unwrap()
+        .restore()
+        .is_none());
+}
+
+#[test]
+fn damaged_saves_do_not_decode() {
+    let saved = SavedGame::new(&game_with_undone_flip(), 42, &[]).unwrap();
+    let text = saved.encode();
+    assert!(SavedGame::decode(&text.replace("solitaire-save 1", "solitaire-save 9")).is_none());
+    assert!(SavedGame::decode(&text.replace("draw=1", "draw=2")).is_none());
+    assert!(SavedGame::decode(&text.replace("moves", "moves t0>x")).is_none());
+    assert!(SavedGame::decode("").is_none());
+}
+
+#[test]
+fn only_numbered_deals_are_saved() {
+    let game = Game::deal(Game::create_deck(Variant::Klondike), Rules::default());
+    assert!(SavedGame::new(&game, 0, &[]).is_none());
+}
+
+#[test]
+fn moves_past_the_piles_do_not_restore() {
+    let text = "\
+solitaire-save 1
+seed 7
+rules draw=1 passes=0 scoring=standard
+time 12
+moves t9>t0
+undone
+log 500:t9>t0
+";
+    let saved = SavedGame::decode(text).unwrap();
+    assert_eq!(saved.rules, Rules::default());
+    assert!(saved.restore().is_none());
+}
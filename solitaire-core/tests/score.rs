@@ -0,0 +1,127 @@
+//! Standard points per move, the clamp at zero, the time bonus and the
+//! Vegas bank.
+
+use solitaire_core::score::{self, standard_points, VegasBank};
+use solitaire_core::{Card, Game, Move, PassLimit, Rank, Rules, Suit, Variant};
+
+fn card(rank: Rank, suit: Suit) -> Card {
+    Card {
+        face_up: true,
+        ..Card::new(rank, suit)
+    }
+}
+
+// A board with every pile empty, for placing just the cards a test needs
+fn empty_board(rules: Rules) -> Game {
+    let mut game = Game::deal(Game::create_deck(Variant::Klondike), rules);
+    game.tableau = vec![Vec::new(); 7];
+    game.stock.clear();
+    game
+}
+
+// Twelve hearts played from the waste onto their Ace for 120 points, with
+// the King of Spades left in the waste to recycle
+fn hearts_played(draw_count: usize) -> Game {
+    let mut game = empty_board(Rules {
+        draw_count,
+        ..Rules::default()
+    });
+    game.discard = vec![card(Rank::King, Suit::Spades)];
+    game.discard.extend(
+        Rank::ALL[..12]
+            .iter()
+            .rev()
+            .map(|&rank| card(rank, Suit::Hearts)),
+    );
+    for _ in 0..12 {
+        assert!(game.apply(Move::DiscardToFoundation { to: 0 }));
+    }
+    assert_eq!(game.score(), 120);
+    game
+}
+
+#[test]
+fn moves_score_windows_points() {
+    let rules = Rules::default();
+    assert_eq!(standard_points(Move::DiscardToTableau { to: 0 }, &rules), 5);
+    assert_eq!(
+        standard_points(Move::DiscardToFoundation { to: 0 }, &rules),
+        10
+    );
+    assert_eq!(
+        standard_points(Move::TableauToFoundation { from: 0, to: 0 }, &rules),
+        10
+    );
+    assert_eq!(standard_points(Move::Flip { pile: 0 }, &rules), 5);
+    assert_eq!(
+        standard_points(Move::FoundationToTableau { from: 0, to: 0 }, &rules),
+        -15
+    );
+    assert_eq!(standard_points(Move::Draw { count: 1 }, &rules), 0);
+}
+
+#[test]
+fn recycling_costs_more_when_drawing_one() {
+    let mut game = hearts_played(1);
+    assert!(game.apply(Move::Recycle));
+    assert_eq!(game.score(), 20);
+
+    let mut game = hearts_played(3);
+    assert!(game.apply(Move::Recycle));
+    assert_eq!(game.score(), 100);
+}
+
+#[test]
+fn penalties_stop_at_zero_and_undo_restores_the_score() {
+    let mut game = empty_board(Rules::default());
+    game.foundation[0] = vec![card(Rank::Ace, Suit::Spades)];
+    game.discard = vec![card(Rank::Ace, Suit::Hearts), card(Rank::Two, Suit::Spades)];
+    game.tableau[0] = vec![card(Rank::Three, Suit::Hearts)];
+    let start = game.clone();
+
+    assert!(game.apply(Move::DiscardToFoundation { to: 0 }));
+    assert!(game.apply(Move::DiscardToFoundation { to: 1 }));
+    assert!(game.apply(Move::FoundationToTableau { from: 0, to: 0 }));
+    assert_eq!(game.score(), 5);
+
+    let mut game = start;
+    assert!(game.apply(Move::DiscardToFoundation { to: 0 }));
+    assert!(game.apply(Move::FoundationToTableau { from: 0, to: 0 }));
+    assert_eq!(game.score(), 0);
+    assert_eq!(game.history()[1].score, 10);
+    assert!(game.undo());
+    assert_eq!(game.score(), 10);
+}
+
+#[test]
+fn time_bonus_starts_at_thirty_seconds() {
+    assert_eq!(score::time_bonus(0), 0);
+    assert_eq!(score::time_bonus(29), 0);
+    assert_eq!(score::time_bonus(30), 23_333);
+    assert_eq!(score::time_bonus(700), 1_000);
+}
+
+#[test]
+fn vegas_pays_per_foundation_card_after_the_buy_in() {
+    let mut game = empty_board(Rules::vegas(1));
+    game.foundation[0] = vec![card(Rank::Ace, Suit::Hearts), card(Rank::Two, Suit::Hearts)];
+    game.foundation[1] = vec![card(Rank::Ace, Suit::Spades)];
+    assert_eq!(game.score(), 3 * score::VEGAS_PAYOUT - score::VEGAS_BUY_IN);
+}
+
+#[test]
+fn vegas_allows_one_pass_drawing_one_and_three_drawing_three() {
+    assert_eq!(Rules::vegas(1).pass_limit, PassLimit::Passes(1));
+    assert_eq!(Rules::vegas(3).pass_limit, PassLimit::Passes(3));
+}
+
+#[test]
+fn the_vegas_bank_carries_each_result_into_the_next_game() {
+    let mut bank = VegasBank::new(100);
+    let first = Game::new(1, Rules::vegas(1));
+    assert_eq!(bank.balance(&first), 48);
+
+    bank.settle(&first);
+    let second = Game::new(2, Rules::vegas(1));
+    assert_eq!(bank.balance(&second), -4);
+}
@@ -0,0 +1,154 @@
+//! The solver's answers and the hints built on them: wins that really
+//! win, a dead position proven lost, and a search that stops at its budget.
+
+use solitaire_core::{
+    position, Card, Game, Move, PassLimit, Rank, Rules, Solution, Solver, Suit, Variant,
+};
+
+// Only red cards show, and the black Threes that could take the Two of
+// Hearts are buried face down beneath it, so no hidden card can ever be
+// reached
+fn dead() -> Game {
+    let up = |rank, suit| Card {
+        face_up: true,
+        ..Card::new(rank, suit)
+    };
+    let mut game = Game::deal(Game::create_deck(Variant::Klondike), Rules::default());
+    game.tableau = vec![
+        vec![up(Rank::Two, Suit::Hearts)],
+        vec![up(Rank::Four, Suit::Hearts)],
+        vec![up(Rank::Six, Suit::Hearts)],
+        vec![up(Rank::Eight, Suit::Hearts)],
+        vec![up(Rank::Ten, Suit::Hearts)],
+        vec![up(Rank::Queen, Suit::Hearts)],
+        vec![up(Rank::Two, Suit::Diamonds)],
+    ];
+    game.stock = vec![
+        Card::new(Rank::Nine, Suit::Clubs),
+        Card::new(Rank::Jack, Suit::Clubs),
+    ];
+    game.discard = vec![up(Rank::Five, Suit::Spades)];
+
+    let placed: Vec<Card> = game
+        .tableau
+        .iter()
+        .flatten()
+        .chain(&game.stock)
+        .chain(&game.discard)
+        .copied()
+        .collect();
+    let buried = Game::create_deck(Variant::Klondike)
+        .into_iter()
+        .filter(|card| {
+            !placed
+                .iter()
+                .any(|p| p.rank == card.rank && p.suit == card.suit)
+        });
+    game.tableau[0].splice(0..0, buried);
+    game
+}
+
+// The pass is used up and the only card left in the waste is a King, which
+// has to go to the empty column before anything else can move
+const KING_IN_THE_WASTE: &str = "\
+solitaire-position 2
+rules variant=klondike draw=1 passes=1 scoring=standard
+score 0
+recycles 0
+s
+w KS
+f0 AH 2H 3H 4H 5H 6H 7H 8H 9H TH
+f1 AD 2D 3D 4D 5D 6D 7D 8D 9D TD JD QD KD
+f2 AC 2C 3C 4C 5C 6C 7C 8C 9C TC JC QC
+f3 AS 2S 3S 4S 5S 6S 7S 8S 9S TS JS
+t0
+t1 QS KC KH JH | QH
+t2
+t3
+t4
+t5
+t6
+";
+
+// Plays `moves` from a fresh deal and returns the game after them
+fn replay(seed: u64, rules: Rules, moves: &[Move]) -> Game {
+    let mut game = Game::new(seed, rules);
+    for &mv in moves {
+        assert!(game.apply(mv), "deal {}: {:?} was refused", seed, mv);
+    }
+    game
+}
+
+#[test]
+fn wins_replay_to_a_won_game() {
+    let solver = Solver::new(50_000);
+    for draw_count in [1, 3] {
+        let rules = Rules {
+            draw_count,
+            ..Rules::default()
+        };
+        let mut wins = 0;
+        for seed in 1..=4 {
+            if let Solution::Win(moves) = solver.solve(&Game::new(seed, rules)) {
+                assert!(replay(seed, rules, &moves).check_game_won());
+                wins += 1;
+            }
+        }
+        assert!(wins > 0, "no win found drawing {}", draw_count);
+    }
+}
+
+#[test]
+fn a_dead_position_is_unwinnable() {
+    let game = dead();
+    assert_eq!(game.tableau.iter().flatten().count() + 3, 52);
+    assert_eq!(Solver::default().solve(&game), Solution::Unwinnable);
+}
+
+#[test]
+fn a_tiny_budget_gives_up() {
+    let game = Game::new(1, Rules::default());
+    assert_eq!(Solver::new(1).solve(&game), Solution::Unknown);
+}
+
+#[test]
+fn wins_keep_to_the_pass_limit() {
+    let solver = Solver::new(50_000);
+    let rules = Rules {
+        draw_count: 3,
+        pass_limit: PassLimit::Passes(2),
+        ..Rules::default()
+    };
+    let mut wins = 0;
+    for seed in 1..=5 {
+        if let Solution::Win(moves) = solver.solve(&Game::new(seed, rules)) {
+            let recycles = moves.iter().filter(|&&mv| mv == Move::Recycle).count();
+            assert!(recycles <= 1, "deal {} recycles {} times", seed, recycles);
+            assert!(replay(seed, rules, &moves).check_game_won());
+            wins += 1;
+        }
+    }
+    assert!(wins > 0, "no win found with one recycle");
+}
+
+#[test]
+fn a_lone_king_in_the_waste_is_played_out() {
+    let game = position::decode(KING_IN_THE_WASTE).unwrap();
+    let Solution::Win(moves) = Solver::default().solve(&game) else {
+        panic!("no win found");
+    };
+    let mut won = game.clone();
+    for &mv in &moves {
+        assert!(won.apply(mv), "{} was refused", mv);
+    }
+    assert!(won.check_game_won());
+}
+
+#[test]
+fn the_hint_for_a_lone_king_plays_it_out() {
+    let game = position::decode(KING_IN_THE_WASTE).unwrap();
+    assert_eq!(
+        Solver::default().hint(&game),
+        Some(Move::DiscardToTableau { to: 0 })
+    );
+}
@@ -0,0 +1,90 @@
+//! Lifetime statistics: their text form, win streaks and best results.
+
+use solitaire_core::stats::{self, Statistics, Tally};
+use solitaire_core::{Game, Rules};
+
+// A game under `rules` with `moves` draws from the stock made
+fn game_with_draws(rules: &str, moves: usize) -> Game {
+    let mut game = Game::new(5, Rules::parse(rules).unwrap());
+    for _ in 0..moves {
+        assert!(game.apply(game.stock_move().unwrap()));
+    }
+    game
+}
+
+#[test]
+fn statistics_round_trip_through_text() {
+    let mut statistics = Statistics::default();
+    let draw_three = game_with_draws("draw=3 passes=0 scoring=standard", 4);
+    statistics.record_win(&draw_three, 184, 5120);
+    statistics.record_loss(&draw_three.rules);
+    statistics.record_loss(&Rules::default());
+    statistics.record_loss(&Rules::spider(2));
+
+    let text = statistics.encode();
+    assert_eq!(
+        text,
+        "klondike-draw1 played=1 won=0 streak=0 longest=0\n\
+         klondike-draw3 played=2 won=1 streak=0 longest=1 time=184 score=5120 moves=4\n\
+         spider-suits2 played=1 won=0 streak=0 longest=0\n"
+    );
+    assert_eq!(Statistics::decode(&text), Some(statistics));
+    assert_eq!(Statistics::decode(""), Some(Statistics::default()));
+
+    for damaged in [
+        "klondike-draw1 played=two",
+        "klondike-draw1 played",
+        "klondike-draw1 colour=red",
+    ] {
+        assert_eq!(Statistics::decode(damaged), None, "{:?}", damaged);
+    }
+}
+
+#[test]
+fn streaks_end_with_a_loss_and_the_longest_is_kept() {
+    let game = game_with_draws("draw=1 passes=0 scoring=standard", 3);
+    let mode = stats::mode(&game.rules);
+    let mut statistics = Statistics::default();
+    statistics.record_win(&game, 300, 1000);
+    statistics.record_win(&game, 200, 800);
+    statistics.record_loss(&game.rules);
+    statistics.record_win(&game, 250, 1200);
+
+    assert_eq!(
+        statistics.tally(&mode),
+        Tally {
+            played: 4,
+            won: 3,
+            streak: 1,
+            longest_streak: 2,
+            best_time: Some(200),
+            best_score: Some(1200),
+            fewest_moves: Some(3),
+        }
+    );
+    assert_eq!(statistics.tally(&mode).win_rate(), 75.0);
+    assert_eq!(statistics.modes().collect::<Vec<_>>(), [mode.as_str()]);
+}
+
+#[test]
+fn vegas_wins_have_no_best_score() {
+    let game = game_with_draws("draw=3 passes=3 scoring=vegas", 2);
+    let mut statistics = Statistics::default();
+    statistics.record_win(&game, 90, 40);
+
+    let tally = statistics.tally(&stats::mode(&game.rules));
+    assert_eq!(tally.won, 1);
+    assert_eq!(tally.best_time, Some(90));
+    assert_eq!(tally.best_score, None);
+}
+
+#[test]
+fn modes_are_named_for_display() {
+    let mode = stats::mode(&Rules::parse("draw=3 passes=3 scoring=vegas").unwrap());
+    assert_eq!(mode, "klondike-draw3");
+    assert_eq!(stats::mode_label(&mode), "Klondike, draw 3");
+
+    let mode = stats::mode(&Rules::spider(1));
+    assert_eq!(mode, "spider-suits1");
+    assert_eq!(stats::mode_label(&mode), "Spider, 1 suit");
+}
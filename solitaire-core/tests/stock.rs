@@ -0,0 +1,143 @@
+//! Drawing from the stock one or three cards at a time, and turning the
+//! waste back over as often as the pass limit allows.
+
+use solitaire_core::{Card, Game, Move, PassLimit, Rules, Variant};
+
+fn game(draw_count: usize, stock: usize) -> Game {
+    let rules = Rules {
+        draw_count,
+        ..Rules::default()
+    };
+    limited_game(rules, stock)
+}
+
+fn limited_game(rules: Rules, stock: usize) -> Game {
+    let mut game = Game::deal(Game::create_deck(Variant::Klondike), rules);
+    game.stock.truncate(stock);
+    game
+}
+
+fn face_down(cards: &[Card]) -> Vec<Card> {
+    cards
+        .iter()
+        .map(|&card| Card {
+            face_up: false,
+            ..card
+        })
+        .collect()
+}
+
+#[test]
+fn games_draw_one_unless_asked_for_three() {
+    assert_eq!(Rules::default().draw_count, 1);
+    let game = game(1, 24);
+    assert_eq!(game.stock_move(), Some(Move::Draw { count: 1 }));
+    assert!(!game.is_legal(Move::Draw { count: 3 }));
+}
+
+#[test]
+fn draw_three_turns_three_with_the_last_on_top() {
+    let mut game = game(3, 24);
+    let top_three = game.stock[21..].to_vec();
+    assert_eq!(game.stock_move(), Some(Move::Draw { count: 3 }));
+    assert!(!game.is_legal(Move::Draw { count: 1 }));
+
+    assert!(game.apply(Move::Draw { count: 3 }));
+    assert_eq!(game.stock.len(), 21);
+    let drawn: Vec<Card> = top_three.iter().rev().copied().collect();
+    assert_eq!(face_down(&game.discard), drawn);
+    assert!(game.discard.iter().all(|card| card.face_up));
+}
+
+#[test]
+fn the_last_draw_takes_what_is_left() {
+    let mut game = game(3, 2);
+    assert_eq!(game.stock_move(), Some(Move::Draw { count: 2 }));
+    assert!(!game.is_legal(Move::Draw { count: 3 }));
+    assert!(game.apply(Move::Draw { count: 2 }));
+    assert_eq!(game.stock_move(), Some(Move::Recycle));
+}
+
+#[test]
+fn a_recycled_waste_draws_again_in_the_same_order() {
+    let mut game = game(3, 7);
+    let stock = game.stock.clone();
+    while let Some(Move::Draw { count }) = game.stock_move() {
+        assert!(game.apply(Move::Draw { count }));
+    }
+    assert_eq!(game.discard.len(), 7);
+
+    assert!(game.apply(Move::Recycle));
+    assert_eq!(game.stock, stock);
+    assert!(game.discard.is_empty());
+}
+
+#[test]
+fn undoing_a_draw_puts_all_three_back() {
+    let mut game = game(3, 24);
+    let stock = game.stock.clone();
+    assert!(game.apply(Move::Draw { count: 3 }));
+    assert!(game.undo());
+    assert_eq!(game.stock, stock);
+    assert!(game.discard.is_empty());
+}
+
+// Draws through the whole stock, then turns the waste over if it may
+fn pass(game: &mut Game) -> bool {
+    while let Some(Move::Draw { count }) = game.stock_move() {
+        assert!(game.apply(Move::Draw { count }));
+    }
+    game.apply(Move::Recycle)
+}
+
+#[test]
+fn pass_limits_count_every_pass_through_the_deck() {
+    assert_eq!(PassLimit::Unlimited.max_recycles(), None);
+    assert_eq!(PassLimit::Passes(1).max_recycles(), Some(0));
+    assert_eq!(PassLimit::Passes(3).max_recycles(), Some(2));
+    assert_eq!(Rules::default().pass_limit, PassLimit::Unlimited);
+}
+
+#[test]
+fn the_waste_stays_put_after_the_last_pass() {
+    let rules = Rules {
+        draw_count: 3,
+        pass_limit: PassLimit::Passes(3),
+        ..Rules::default()
+    };
+    let mut game = limited_game(rules, 5);
+    assert_eq!(game.recycles_left(), Some(2));
+    assert!(pass(&mut game));
+    assert!(pass(&mut game));
+    assert_eq!(game.recycles(), 2);
+    assert_eq!(game.recycles_left(), Some(0));
+
+    assert!(!pass(&mut game));
+    assert_eq!(game.stock_move(), None);
+    assert_eq!(game.discard.len(), 5);
+}
+
+#[test]
+fn undoing_a_recycle_gives_the_pass_back() {
+    let rules = Rules {
+        draw_count: 1,
+        pass_limit: PassLimit::Passes(2),
+        ..Rules::default()
+    };
+    let mut game = limited_game(rules, 2);
+    assert!(pass(&mut game));
+    assert_eq!(game.recycles_left(), Some(0));
+    assert!(game.undo());
+    assert_eq!(game.recycles(), 0);
+    assert_eq!(game.stock_move(), Some(Move::Recycle));
+}
+
+#[test]
+fn without_a_limit_the_waste_always_goes_back() {
+    let mut game = game(1, 3);
+    for _ in 0..10 {
+        assert!(pass(&mut game));
+    }
+    assert_eq!(game.recycles(), 10);
+    assert_eq!(game.recycles_left(), None);
+}
@@ -0,0 +1,143 @@
+//! Undo and redo of every kind of move, which must give back exactly the
+//! board the move started from.
+
+use solitaire_core::{Card, Game, Move, Pile, Rank, Rules, Suit, Variant};
+
+type Board = (Vec<Vec<Card>>, Vec<Vec<Card>>, Vec<Card>, Vec<Card>);
+
+fn board(game: &Game) -> Board {
+    (
+        game.tableau.clone(),
+        game.foundation.clone(),
+        game.stock.clone(),
+        game.discard.clone(),
+    )
+}
+
+fn moves(game: &Game) -> Vec<Move> {
+    game.history().iter().map(|record| record.mv).collect()
+}
+
+fn card(rank: Rank, suit: Suit) -> Card {
+    Card {
+        face_up: true,
+        ..Card::new(rank, suit)
+    }
+}
+
+// A board where every kind of move can be played once: a run to uncover a
+// face-down card, an Ace and a Two to build on, and a King for the gap
+fn game() -> Game {
+    let mut game = Game::deal(Game::create_deck(Variant::Klondike), Rules::default());
+    game.tableau = vec![
+        vec![
+            Card::new(Rank::Five, Suit::Clubs),
+            card(Rank::Nine, Suit::Spades),
+        ],
+        vec![card(Rank::Ten, Suit::Hearts)],
+        vec![card(Rank::Ace, Suit::Clubs)],
+        vec![card(Rank::Three, Suit::Hearts)],
+        vec![],
+        vec![],
+        vec![],
+    ];
+    game.foundation = vec![vec![card(Rank::Ace, Suit::Spades)], vec![], vec![], vec![]];
+    game.stock = vec![Card::new(Rank::Four, Suit::Diamonds)];
+    game.discard = vec![
+        card(Rank::King, Suit::Diamonds),
+        card(Rank::Two, Suit::Spades),
+    ];
+    game
+}
+
+// Plays `mv`, undoes it and redoes it, checking the board at every step
+fn assert_round_trip(game: &mut Game, mv: Move) {
+    let before = board(game);
+    assert!(game.apply(mv), "{:?} was refused", mv);
+    let after = board(game);
+
+    assert!(game.undo());
+    assert_eq!(board(game), before, "undo of {:?}", mv);
+    assert!(game.can_redo());
+    assert!(game.redo());
+    assert_eq!(board(game), after, "redo of {:?}", mv);
+    assert!(!game.can_redo());
+}
+
+#[test]
+fn every_kind_of_move_undoes_and_redoes() {
+    let mut game = game();
+    assert_round_trip(&mut game, Move::DiscardToFoundation { to: 0 });
+    assert_round_trip(&mut game, Move::DiscardToTableau { to: 4 });
+    assert_round_trip(&mut game, Move::TableauToFoundation { from: 2, to: 1 });
+    assert_round_trip(&mut game, Move::FoundationToTableau { from: 0, to: 3 });
+    assert_round_trip(
+        &mut game,
+        Move::TableauToTableau {
+            from: 0,
+            to: 1,
+            count: 1,
+        },
+    );
+    assert_round_trip(&mut game, Move::Draw { count: 1 });
+    assert_round_trip(&mut game, Move::Recycle);
+}
+
+#[test]
+fn a_flip_is_undone_with_the_move_that_caused_it() {
+    let mut game = game();
+    let before = board(&game);
+    let mv = Move::TableauToTableau {
+        from: 0,
+        to: 1,
+        count: 1,
+    };
+    assert!(game.apply(mv));
+    assert_eq!(moves(&game), [mv, Move::Flip { pile: 0 }]);
+    assert!(game.tableau[0][0].face_up);
+
+    assert!(game.undo());
+    assert_eq!(board(&game), before);
+    assert!(!game.can_undo());
+    assert!(game.redo());
+    assert_eq!(moves(&game), [mv, Move::Flip { pile: 0 }]);
+}
+
+#[test]
+fn a_new_move_clears_the_redo_stack() {
+    let mut game = game();
+    assert!(game.apply(Move::Draw { count: 1 }));
+    assert!(game.undo());
+    assert!(game.can_redo());
+
+    assert!(game.apply(Move::DiscardToFoundation { to: 0 }));
+    assert!(!game.can_redo());
+    assert!(!game.redo());
+}
+
+#[test]
+fn refused_moves_change_nothing() {
+    let mut game = game();
+    let before = board(&game);
+    assert!(!game.apply(Move::Recycle));
+    assert!(!game.apply(Move::DiscardToTableau { to: 1 }));
+    assert!(!game.apply(Move::Flip { pile: 1 }));
+    assert_eq!(board(&game), before);
+    assert!(!game.can_undo());
+    assert!(!game.undo());
+}
+
+#[test]
+fn automatic_moves_are_left_to_the_game() {
+    let mut game = game();
+    game.tableau[4] = vec![Card::new(Rank::Two, Suit::Diamonds)];
+    let flip = Move::Flip { pile: 4 };
+    assert!(game.is_legal(flip));
+    assert!(!game.legal_moves().contains(&flip));
+    assert!(!game.apply(flip));
+    assert!(!game.can_undo());
+
+    // Dragging a finished Spider run to a foundation is not a move either
+    let run = Move::between(Pile::Tableau(0), 13, Pile::Foundation(0));
+    assert_eq!(run, None);
+}
@@ -0,0 +1,129 @@
+use solitaire_core::{Game, Pile, Variant};
+
+// The keyboard cursor. In the tableau it sits on one face-up card, counted
+// from the bottom of the pile, and covers the run from there to the top;
+// elsewhere only the top card can be used.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Focus {
+    pub pile: Pile,
+    pub index: usize,
+}
+
+impl Focus {
+    pub fn on_top(game: &Game, pile: Pile) -> Self {
+        Focus {
+            pile,
+            index: game.pile(pile).len().saturating_sub(1),
+        }
+    }
+
+    // The same pile after the board changed under the cursor, back on a
+    // card that is still there and face up
+    pub fn settle(self, game: &Game) -> Self {
+        let cards = game.pile(self.pile);
+        match self.pile {
+            Pile::Tableau(_) => {
+                let first_up = cards
+                    .iter()
+                    .position(|card| card.face_up)
+                    .unwrap_or(cards.len());
+                Focus {
+                    index: self.index.max(first_up).min(cards.len().saturating_sub(1)),
+                    ..self
+                }
+            }
+            pile => Focus::on_top(game, pile),
+        }
+    }
+
+    // Cards a pick-up at the cursor takes
+    pub fn count(self, game: &Game) -> usize {
+        let len = game.pile(self.pile).len();
+        match self.pile {
+            Pile::Tableau(_) => len.saturating_sub(self.settle(game).index),
+            _ => len.min(1),
+        }
+    }
+
+    pub fn left(self, game: &Game) -> Self {
+        self.step(game, -1)
+    }
+
+    pub fn right(self, game: &Game) -> Self {
+        self.step(game, 1)
+    }
+
+    // Up the run, then on to the pile above the column
+    pub fn up(self, game: &Game) -> Self {
+        let focus = self.settle(game);
+        match focus.pile {
+            Pile::Tableau(column) => {
+                let cards = game.pile(focus.pile);
+                if focus.index > 0 && cards[focus.index - 1].face_up {
+                    Focus {
+                        index: focus.index - 1,
+                        ..focus
+                    }
+                } else {
+                    Focus::on_top(game, pile_above(game, column))
+                }
+            }
+            _ => focus,
+        }
+    }
+
+    // Down the run, or from the top row onto the top card of the column
+    // below
+    pub fn down(self, game: &Game) -> Self {
+        let focus = self.settle(game);
+        match focus.pile {
+            Pile::Tableau(_) => Focus {
+                index: (focus.index + 1).min(game.pile(focus.pile).len().saturating_sub(1)),
+                ..focus
+            },
+            pile => Focus::on_top(game, Pile::Tableau(column_below(game, pile))),
+        }
+    }
+
+    // Along the row of piles the cursor is in, stopping at either end
+    fn step(self, game: &Game, delta: isize) -> Self {
+        let row: Vec<Pile> = match self.pile {
+            Pile::Tableau(_) => (0..game.tableau.len()).map(Pile::Tableau).collect(),
+            _ => [Pile::Stock]
+                .iter()
+                .copied()
+                .chain(has_waste(game).then_some(Pile::Discard))
+                .chain((0..game.foundation.len()).map(Pile::Foundation))
+                .collect(),
+        };
+        let i = row.iter().position(|&pile| pile == self.pile).unwrap_or(0);
+        let j = (i as isize + delta).clamp(0, row.len() as isize - 1);
+        Focus::on_top(game, row[j as usize])
+    }
+}
+
+// Spider deals from the stock straight onto the columns
+fn has_waste(game: &Game) -> bool {
+    game.rules.variant == Variant::Klondike
+}
+
+// The stock and waste sit over the first columns and the foundations over
+// the last ones, as on the board
+fn column_below(game: &Game, pile: Pile) -> usize {
+    match pile {
+        Pile::Stock => 0,
+        Pile::Discard => 1,
+        Pile::Foundation(i) => game.tableau.len() - game.foundation.len() + i,
+        Pile::Tableau(column) => column,
+    }
+}
+
+fn pile_above(game: &Game, column: usize) -> Pile {
+    let first_foundation = game.tableau.len() - game.foundation.len();
+    match column {
+        0 => Pile::Stock,
+        column if column < first_foundation && has_waste(game) => Pile::Discard,
+        column if column < first_foundation => Pile::Stock,
+        column => Pile::Foundation(column - first_foundation),
+    }
+}
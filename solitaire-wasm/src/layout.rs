@@ -0,0 +1,56 @@
+use web_sys::CanvasRenderingContext2d;
+
+// Everything is drawn and hit-tested in board units, the space the pile
+// positions and card sizes are given in. The layout maps that space, as
+// large as the piles and the toolbar arranged around them, onto a canvas
+// filling the window at the screen's full resolution: the board is scaled
+// to fit, centred across and kept at the top, so cards are as large as the
+// viewport allows and stay sharp on HiDPI screens.
+#[derive(Clone, Copy)]
+pub struct Layout {
+    pub width: u32, // Canvas size in device pixels
+    pub height: u32,
+    pixel_ratio: f64, // Device pixels per CSS pixel
+    scale: f64,       // Device pixels per board unit
+    offset_x: f64,    // Left margin in device pixels
+}
+
+impl Layout {
+    pub fn fit(
+        css_width: f64,
+        css_height: f64,
+        pixel_ratio: f64,
+        (board_width, board_height): (f64, f64),
+    ) -> Self {
+        let width = (css_width * pixel_ratio).round().max(1.0);
+        let height = (css_height * pixel_ratio).round().max(1.0);
+        let scale = (width / board_width).min(height / board_height);
+        Layout {
+            width: width as u32,
+            height: height as u32,
+            pixel_ratio,
+            scale,
+            offset_x: (width - board_width * scale) / 2.0,
+        }
+    }
+
+    // Makes the context draw in board units
+    pub fn apply(&self, ctx: &CanvasRenderingContext2d) {
+        ctx.set_transform(self.scale, 0.0, 0.0, self.scale, self.offset_x, 0.0)
+            .unwrap();
+    }
+
+    // The board point under a point given in CSS pixels from the canvas'
+    // top-left corner
+    pub fn board_point(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        (
+            (x * self.pixel_ratio - self.offset_x) / self.scale,
+            y * self.pixel_ratio / self.scale,
+        )
+    }
+
+    // For distances the pointer travels, which are measured in CSS pixels
+    pub fn board_units_per_css_pixel(&self) -> f64 {
+        self.pixel_ratio / self.scale
+    }
+}
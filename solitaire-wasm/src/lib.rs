@@ -1,86 +1,235 @@
-extern crate rand;
+extern crate js_sys;
+extern crate solitaire_core;
 extern crate wasm_bindgen;
 extern crate web_sys;
 
-use rand::seq::SliceRandom;
-use rand::thread_rng;
+mod focus;
+mod layout;
+mod overlay;
+mod playback;
+mod pointer;
+mod statistics;
+mod storage;
+mod toolbar;
+
+use focus::Focus;
+use layout::Layout;
+use overlay::{Highlight, Overlay};
+use playback::{Control, Playback};
+use pointer::Press;
+use solitaire_core::replay::{Action, MoveLog};
+use solitaire_core::score::{self, VegasBank};
+use solitaire_core::stats::{self, Statistics};
+use solitaire_core::{notation, position};
+use solitaire_core::{
+    Card, Game, Move, PassLimit, Pile, Rank, Rules, SavedGame, Scoring, Solver, Suit, Variant,
+};
+use statistics::Control as StatisticsControl;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
+use toolbar::{Button, Placement};
 use wasm_bindgen::closure::Closure;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
-use web_sys::{window, CanvasRenderingContext2d, HtmlCanvasElement, HtmlImageElement, MouseEvent};
+use web_sys::{
+    window, CanvasRenderingContext2d, HtmlCanvasElement, HtmlImageElement, KeyboardEvent,
+    MouseEvent, PointerEvent, UrlSearchParams,
+};
 
 const CARD_WIDTH: f64 = 140.0;
 const CARD_HEIGHT: f64 = 190.0;
 const PILE_GAP: f64 = 50.0;
-const CANVAS_WIDTH: f64 = 7.0 * CARD_WIDTH + 40.0 * PILE_GAP; // 7 tableau piles + gaps
-const CANVAS_HEIGHT: f64 = 5.0 * CARD_HEIGHT + 20.0 * PILE_GAP; // Enough for stacked tableau cards
-
-#[derive(Clone)]
-struct Card {
-    rank: String,
-    suit: String,
-    face_up: bool,
-    x: f64,
-    y: f64,
-    width: f64,
-    height: f64,
+const TABLEAU_Y: f64 = 250.0;
+const TABLEAU_OVERLAP: f64 = 60.0; // Visible part of a card covered by the next one
+const DRAG_OVERLAP: f64 = 30.0;
+const FAN_OFFSET: f64 = 40.0; // Horizontal spread of the fanned discard cards
+const RUN_OFFSET: f64 = 60.0; // Horizontal spread of the finished Spider runs
+const VEGAS_BANK_KEY: &str = "solitaire.vegas_bank";
+const SAVED_GAME_KEY: &str = "solitaire.saved_game";
+const STATISTICS_KEY: &str = "solitaire.statistics";
+const HINT_DURATION_MS: f64 = 2500.0;
+const CLICK_SLOP: f64 = 5.0; // Mouse travel, in CSS pixels, below which a press counts as a click
+const TAP_SLOP: f64 = 12.0; // The same for a finger or pen, which wobbles more
+const LONG_PRESS_MS: i32 = 500;
+const AUTO_COMPLETE_STEP_MS: i32 = 120; // Delay between cards sent up by auto-complete
+const REPLAY_FRAME_MS: i32 = 50;
+const WIN_SCREEN_MS: f64 = 10_000.0; // The board comes back once the win message has faded
+const HINT_NODE_BUDGET: usize = 20_000; // Keeps the solver behind a hint under a second
+const STATISTICS_ORIGIN: (f64, f64) = (2.0 * PILE_GAP, 2.0 * PILE_GAP);
+
+const HUD_LINE_HEIGHT: f64 = 36.0;
+const HUD_HEIGHT: f64 = 6.0 * HUD_LINE_HEIGHT; // Vegas with a pass limit shows six lines
+const HUD_WIDTH: f64 = 320.0; // Room for the longest line beside the replay controls
+
+// Board units the piles take up: the tableau, down to a Klondike pile of 6
+// face-down cards and a full run, or a Spider column a few cards longer
+fn piles_size(variant: Variant) -> (f64, f64) {
+    let longest_column = match variant {
+        Variant::Klondike => 19,
+        Variant::Spider { .. } => 25,
+    };
+    (
+        PILE_GAP + variant.tableau_piles() as f64 * (CARD_WIDTH + PILE_GAP),
+        TABLEAU_Y + (longest_column - 1) as f64 * TABLEAU_OVERLAP + CARD_HEIGHT + PILE_GAP,
+    )
 }
 
-impl Card {
-    fn new(rank: &str, suit: &str) -> Self {
-        Card {
-            rank: rank.to_string(),
-            suit: suit.to_string(),
-            face_up: false,
-            x: 0.0,
-            y: 0.0,
-            width: CARD_WIDTH,
-            height: CARD_HEIGHT,
+// Where the toolbar, the HUD and the replay controls go around the piles,
+// and the board they make up together, which the layout fits into the
+// window
+#[derive(Clone, Copy)]
+struct Panel {
+    toolbar: Placement,
+    hud: (f64, f64), // Top-left corner of the text lines
+    playback: (f64, f64),
+    board: (f64, f64),
+}
+
+impl Panel {
+    // For wide windows: the toolbar in rows of five to the right of the
+    // piles, past the foundations in Klondike and past the ten columns in
+    // Spider, with the HUD and the replay controls under it
+    fn beside(variant: Variant) -> Self {
+        let x = match variant {
+            Variant::Klondike => PILE_GAP + 4.5 * CARD_WIDTH + 4.0 * (CARD_WIDTH + PILE_GAP),
+            Variant::Spider { .. } => piles_size(variant).0,
+        };
+        let toolbar = Placement {
+            origin: (x, PILE_GAP),
+            columns: 5,
+        };
+        let hud = (x, PILE_GAP + toolbar.size().1 + PILE_GAP);
+        let playback = (x, hud.1 + HUD_HEIGHT + PILE_GAP);
+        let width = toolbar.size().0.max(playback::SIZE.0);
+        Panel {
+            toolbar,
+            hud,
+            playback,
+            board: (
+                x + width + PILE_GAP,
+                piles_size(variant)
+                    .1
+                    .max(playback.1 + playback::SIZE.1 + PILE_GAP),
+            ),
         }
     }
 
-    fn draw(
-        &self,
-        ctx: &CanvasRenderingContext2d,
-        card_images: &HashMap<String, HtmlImageElement>,
-    ) {
-        // Build the key for the image
-        let key = if self.face_up {
-            format!("{}_{}", self.suit, self.rank) // e.g. "hearts_A"
-        } else {
-            "cover".to_string()
+    // For tall windows: the toolbar below the piles, wrapping at their
+    // width, then the HUD with the replay controls to its right
+    fn below(variant: Variant) -> Self {
+        let (width, height) = piles_size(variant);
+        let columns = (width - PILE_GAP + toolbar::BUTTON_GAP)
+            / (toolbar::BUTTON_WIDTH + toolbar::BUTTON_GAP);
+        let toolbar = Placement {
+            origin: (PILE_GAP, height),
+            columns: (columns as usize).clamp(1, Button::ALL.len()),
         };
+        let hud = (PILE_GAP, height + toolbar.size().1 + PILE_GAP);
+        let playback = (PILE_GAP + HUD_WIDTH, hud.1);
+        Panel {
+            toolbar,
+            hud,
+            playback,
+            board: (
+                width.max(playback.0 + playback::SIZE.0 + PILE_GAP),
+                hud.1 + HUD_HEIGHT.max(playback::SIZE.1) + PILE_GAP,
+            ),
+        }
+    }
 
-        if let Some(img) = card_images.get(&key) {
-            // Just draw the image directly (no new load, no onload event)
-            ctx.draw_image_with_html_image_element(img, self.x, self.y)
-                .unwrap();
+    // Whichever of the two shows the cards larger in a window of the given
+    // size in CSS pixels
+    fn fit(variant: Variant, (width, height): (f64, f64)) -> Self {
+        let scale = |panel: &Panel| (width / panel.board.0).min(height / panel.board.1);
+        let (beside, below) = (Panel::beside(variant), Panel::below(variant));
+        if scale(&below) > scale(&beside) {
+            below
+        } else {
+            beside
         }
     }
+}
 
-    fn contains(&self, x: f64, y: f64) -> bool {
-        x >= self.x && x <= self.x + self.width && y >= self.y && y <= self.y + self.height
+// Top-left corner of a card slot on the board. Spider has no waste, and its
+// finished runs are fanned out above the last columns.
+fn pile_position(variant: Variant, pile: Pile, index: usize) -> (f64, f64) {
+    match (variant, pile) {
+        (_, Pile::Tableau(i)) => (
+            PILE_GAP + i as f64 * (CARD_WIDTH + PILE_GAP),
+            TABLEAU_Y + index as f64 * TABLEAU_OVERLAP,
+        ),
+        (Variant::Klondike, Pile::Foundation(i)) => (
+            PILE_GAP + 4.5 * CARD_WIDTH + (i as f64 * (CARD_WIDTH + PILE_GAP)),
+            PILE_GAP,
+        ),
+        (Variant::Spider { .. }, Pile::Foundation(i)) => (
+            PILE_GAP + 9.0 * (CARD_WIDTH + PILE_GAP) - (7 - i) as f64 * RUN_OFFSET,
+            PILE_GAP,
+        ),
+        (_, Pile::Stock) => (PILE_GAP, PILE_GAP),
+        (_, Pile::Discard) => (PILE_GAP + CARD_WIDTH + PILE_GAP, PILE_GAP),
     }
 }
 
+// Helper to load one image
+fn load_image(src: &str) -> HtmlImageElement {
+    let img = HtmlImageElement::new().unwrap();
+    img.set_src(src);
+    img
+}
+
+// Length shared by the spans `start..start + len` and `other..other + other_len`
+fn overlap(start: f64, len: f64, other: f64, other_len: f64) -> f64 {
+    ((start + len).min(other + other_len) - start.max(other)).max(0.0)
+}
+
+fn contains(left: f64, top: f64, height: f64, x: f64, y: f64) -> bool {
+    x >= left && x <= left + CARD_WIDTH && y >= top && y <= top + height
+}
+
+// Self-rescheduling requestAnimationFrame callback
+type AnimationFrame = Rc<RefCell<Option<Closure<dyn FnMut()>>>>;
+
+// Cards picked up by the player; they stay in their pile until dropped
+struct Drag {
+    source: Pile,
+    count: usize,
+    offset_x: f64,
+    offset_y: f64,
+    start_x: f64,
+    start_y: f64,
+    x: f64,
+    y: f64,
+}
+
 struct GameState {
-    tableau: Vec<Vec<Card>>,                                    // 7 tableau piles
-    foundation: Vec<Vec<Card>>,                                 // 4 foundation piles
-    stock: Vec<Card>,                                           // Draw pile
-    discard: Vec<Card>,                                         // Discard pile
-    selected_card: Option<(Card, usize, usize)>, // (Card, source pile index, source type)
-    dragging_card: Option<(Vec<Card>, f64, f64, usize, usize)>, // Vec<Card> to store multiple cards
+    game: Game,
+    selected_card: Option<(Pile, usize)>, // Cards picked by a click: source pile and count
+    dragging_card: Option<Drag>,
+    press: Option<Press>, // Pointer currently down on the board
+    presses: u32,
+    focus: Option<Focus>,     // Keyboard cursor, shown once the keyboard is used
+    started_at: f64,          // Date.now() when the game was dealt
+    finished_at: Option<f64>, // Date.now() when the game was won
+    vegas_bank: Option<VegasBank>, // Cumulative Vegas session, in Vegas games
+    overlay: Overlay,         // Hints and other marks drawn over the board
+    auto_completing: bool,    // Cards are being sent to the foundations
+    log: Option<MoveLog>,     // Everything the player did, in games dealt from a seed
+    playback: Option<Playback>, // Replay of the finished game being watched
+    statistics: Statistics,   // Lifetime results, by variant and draw mode
+    showing_statistics: bool, // The statistics screen covers the board
+    panel: Panel,             // Toolbar and HUD, arranged for the window's shape
+    layout: Layout,
     canvas: HtmlCanvasElement,
-    card_images: HashMap<String, HtmlImageElement>,
+    card_images: HashMap<(Suit, Rank), HtmlImageElement>,
+    cover_image: HtmlImageElement,
     ctx: CanvasRenderingContext2d,
 }
 
 impl GameState {
     fn draw_background(&self) {
-        self.ctx.set_fill_style(&"green".into());
+        self.ctx.set_fill_style_str("green");
         self.ctx.fill_rect(
             0.0,
             0.0,
@@ -89,82 +238,145 @@ impl GameState {
         );
     }
 
-    fn create_deck() -> Vec<Card> {
-        let suits = vec!["hearts", "diamonds", "clubs", "spades"];
-        let ranks = vec![
-            "A", "2", "3", "4", "5", "6", "7", "8", "9", "10", "J", "Q", "K",
-        ];
-        let mut deck = Vec::new();
+    // `resumed` is the save `game` was restored from, if any
+    fn new(
+        ctx: CanvasRenderingContext2d,
+        canvas: HtmlCanvasElement,
+        game: Game,
+        resumed: Option<&SavedGame>,
+    ) -> Self {
+        let played_seconds = resumed.map_or(0, |saved| saved.elapsed_seconds);
+        let log = match resumed {
+            Some(saved) => Some(MoveLog::from_entries(
+                saved.seed,
+                saved.rules,
+                saved.log.clone(),
+            )),
+            None => MoveLog::new(&game),
+        };
+        let panel = Panel::beside(game.rules.variant);
+        // Preload images
+        let card_images = GameState::preload_images();
+        let cover_image = load_image("./sprites/cover/cover.jpg");
+
+        let mut state = GameState {
+            game,
+            selected_card: None,
+            dragging_card: None,
+            press: None,
+            presses: 0,
+            focus: None,
+            started_at: js_sys::Date::now() - played_seconds as f64 * 1000.0,
+            finished_at: None,
+            vegas_bank: None,
+            overlay: Overlay::default(),
+            auto_completing: false,
+            log,
+            playback: None,
+            statistics: load_statistics(),
+            showing_statistics: false,
+            panel,
+            layout: Layout::fit(panel.board.0, panel.board.1, 1.0, panel.board),
+            canvas,
+            ctx,
+            card_images,
+            cover_image,
+        };
+        state.open_vegas_bank(resumed.is_some());
+        state
+    }
+
+    // Preload images for all suits/ranks; the back is loaded separately
+    fn preload_images() -> HashMap<(Suit, Rank), HtmlImageElement> {
+        let mut images = HashMap::new();
 
-        for suit in suits {
-            for rank in &ranks {
-                deck.push(Card::new(rank, suit));
+        // For each suit/rank, e.g. "./sprites/hearts/A.jpg"
+        for suit in Suit::ALL {
+            for rank in Rank::ALL {
+                let path = format!("./sprites/{}/{}.jpg", suit.name(), rank.label());
+                images.insert((suit, rank), load_image(&path));
             }
         }
 
-        deck
+        images
     }
 
-    fn new(ctx: CanvasRenderingContext2d, canvas: HtmlCanvasElement) -> Self {
-        let mut deck = GameState::create_deck();
-        deck.shuffle(&mut thread_rng());
+    fn is_spider(&self) -> bool {
+        self.game.rules.variant != Variant::Klondike
+    }
 
-        // Preload images
-        let card_images = GameState::preload_images();
+    fn pile_position(&self, pile: Pile, index: usize) -> (f64, f64) {
+        pile_position(self.game.rules.variant, pile, index)
+    }
 
-        let mut tableau = vec![vec![]; 7];
-        for i in 0..7 {
-            for j in 0..=i {
-                let mut card = deck.pop().unwrap();
-                card.face_up = j == i; // Only the top card in each pile is face-up
-                tableau[i].push(card);
-            }
-        }
+    fn draw_card(&self, card: &Card, x: f64, y: f64) {
+        let img = if card.face_up {
+            self.card_images.get(&(card.suit, card.rank))
+        } else {
+            Some(&self.cover_image)
+        };
 
-        GameState {
-            tableau,
-            foundation: vec![vec![]; 4],
-            stock: deck,
-            discard: Vec::new(),
-            selected_card: None,
-            dragging_card: None,
-            canvas,
-            ctx,
-            card_images,
+        if let Some(img) = img {
+            // Just draw the image directly (no new load, no onload event)
+            self.ctx
+                .draw_image_with_html_image_element(img, x, y)
+                .unwrap();
         }
     }
 
-    // Preload images for all suits/ranks plus the back
-    fn preload_images() -> HashMap<String, HtmlImageElement> {
-        let suits = ["hearts", "diamonds", "clubs", "spades"];
-        let ranks = [
-            "A", "2", "3", "4", "5", "6", "7", "8", "9", "10", "J", "Q", "K",
-        ];
-        let mut images = HashMap::new();
+    fn draw_empty_slot(&self, pile: Pile) {
+        let (x, y) = self.pile_position(pile, 0);
+        self.ctx.set_stroke_style_str("black");
+        self.ctx.set_line_width(2.0);
+        self.ctx.stroke_rect(x, y, CARD_WIDTH, CARD_HEIGHT);
+    }
 
-        // Helper to load one image
-        let load_image = |src: &str| {
-            let img = HtmlImageElement::new().unwrap();
-            img.set_src(src);
-            img
+    // Marks the empty stock slot with whether the discard pile can still go back
+    fn draw_recycle_indicator(&self) {
+        let (x, y) = self.pile_position(Pile::Stock, 0);
+        let (symbol, color) = if self.game.recycles_left() == Some(0) {
+            ("✕", "darkred")
+        } else {
+            ("↻", "white")
         };
 
-        // For each suit/rank
-        for suit in suits.iter() {
-            for rank in ranks.iter() {
-                let key = format!("{}_{}", suit, rank);
-                let path = format!("./sprites/{}/{}.jpg", suit, rank);
-                images.insert(key, load_image(&path));
-            }
+        self.ctx.set_font("96px Arial");
+        self.ctx.set_fill_style_str(color);
+        self.ctx
+            .fill_text(
+                symbol,
+                x + CARD_WIDTH / 2.0 - 36.0,
+                y + CARD_HEIGHT / 2.0 + 32.0,
+            )
+            .unwrap();
+    }
+
+    // Cards of a pile that are not currently being dragged around
+    fn resting_cards(&self, pile: Pile) -> &[Card] {
+        let cards = self.game.pile(pile);
+        match &self.dragging_card {
+            Some(drag) if drag.source == pile => &cards[..cards.len() - drag.count],
+            _ => cards,
         }
+    }
 
-        // Add the back/cover
-        images.insert("cover".to_string(), load_image("./sprites/cover/cover.jpg"));
+    // Discard cards shown side by side: as many as one draw turns over
+    fn discard_fan(&self, len: usize) -> usize {
+        len.min(self.game.rules.draw_count)
+    }
 
-        images
+    // Only the rightmost card of the fan can be picked up
+    fn discard_top_position(&self) -> (f64, f64) {
+        let (x, y) = self.pile_position(Pile::Discard, 0);
+        let fan = self.discard_fan(self.game.discard.len());
+        (x + fan.saturating_sub(1) as f64 * FAN_OFFSET, y)
     }
 
     fn render(&mut self) {
+        // The margins around the board are painted too
+        self.ctx
+            .set_transform(1.0, 0.0, 0.0, 1.0, 0.0, 0.0)
+            .unwrap();
         self.ctx.clear_rect(
             0.0,
             0.0,
@@ -172,358 +384,1025 @@ impl GameState {
             self.canvas.height() as f64,
         );
         self.draw_background();
+        self.layout.apply(&self.ctx);
+        if self.showing_statistics {
+            let mode = stats::mode(&self.game.rules);
+            statistics::draw(&self.ctx, STATISTICS_ORIGIN, &self.statistics, &mode);
+            return;
+        }
 
         // Render tableau piles with increased vertical spacing
-        for (i, pile) in self.tableau.iter_mut().enumerate() {
-            let length = pile.len();
-            let overlap = 60.0;
-            for (j, card) in pile.iter_mut().enumerate() {
-                card.x = PILE_GAP + i as f64 * (CARD_WIDTH + PILE_GAP);
-                card.y = 200.0 + j as f64 * overlap + 50.0;
-
-                // If this is the TOPMOST card in the pile, it's fully visible:
-                if j == length - 1 {
-                    card.height = CARD_HEIGHT;
-                } else {
-                    // This card is underneath. Only the top <overlap> is visible.
-                    card.height = overlap;
-                }
+        for i in 0..self.game.tableau.len() {
+            let pile = Pile::Tableau(i);
+            for (j, card) in self.resting_cards(pile).iter().enumerate() {
+                let (x, y) = self.pile_position(pile, j);
+                self.draw_card(card, x, y);
+            }
+        }
 
-                card.draw(&self.ctx, &self.card_images);
+        // Render foundation and stock piles; only their top card is visible
+        let flat_piles = (0..self.game.foundation.len())
+            .map(Pile::Foundation)
+            .chain([Pile::Stock]);
+        for pile in flat_piles {
+            let cards = self.resting_cards(pile);
+            // A finished Spider run shows its King, and the slots for runs
+            // still to come stay bare
+            let run = self.is_spider() && pile != Pile::Stock;
+            let card = if run { cards.first() } else { cards.last() };
+            if let Some(card) = card {
+                let (x, y) = self.pile_position(pile, 0);
+                self.draw_card(card, x, y);
+            } else if !run {
+                self.draw_empty_slot(pile);
             }
         }
+        if self.game.stock.is_empty() && !self.is_spider() {
+            self.draw_recycle_indicator();
+        }
 
-        // Render foundation piles
-        for (i, pile) in self.foundation.iter_mut().enumerate() {
-            if let Some(card) = pile.last_mut() {
-                card.x = PILE_GAP + 4.5 * CARD_WIDTH + (i as f64 * (CARD_WIDTH + PILE_GAP));
-                card.y = PILE_GAP;
-                card.draw(&self.ctx, &self.card_images);
-            } else {
-                // Draw empty foundation slots
-                self.ctx.set_stroke_style(&JsValue::from_str("black"));
-                self.ctx.set_line_width(2.0);
-                self.ctx.stroke_rect(
-                    PILE_GAP + 4.5 * CARD_WIDTH + (i as f64 * (CARD_WIDTH + PILE_GAP)),
-                    PILE_GAP,
-                    CARD_WIDTH,
-                    CARD_HEIGHT,
+        // Render the discard pile, fanned out in draw-three games; Spider
+        // has none
+        let discard = self.resting_cards(Pile::Discard);
+        if !discard.is_empty() {
+            let (x, y) = self.pile_position(Pile::Discard, 0);
+            let fan = self.discard_fan(discard.len());
+            for (i, card) in discard[discard.len() - fan..].iter().enumerate() {
+                self.draw_card(card, x + i as f64 * FAN_OFFSET, y);
+            }
+        } else if !self.is_spider() {
+            self.draw_empty_slot(Pile::Discard);
+        }
+
+        // The toolbar waits while a replay is on
+        let replaying = self.playback.is_some();
+        for button in Button::ALL {
+            let enabled = !replaying
+                && match button {
+                    Button::NewGame
+                    | Button::Export
+                    | Button::ExportDeal
+                    | Button::Import
+                    | Button::Statistics => true,
+                    Button::Hint => self.finished_at.is_none(),
+                    Button::Undo => self.game.can_undo(),
+                    Button::Redo => self.game.can_redo(),
+                    Button::Replay => self.finished_at.is_some() && self.log.is_some(),
+                };
+            button.draw(&self.ctx, self.panel.toolbar, enabled);
+        }
+
+        self.draw_hud();
+        if let Some(playback) = &self.playback {
+            playback.draw(&self.ctx, self.panel.playback);
+        }
+
+        if let Some((pile, count)) = self.selected_card {
+            self.cards_highlight(pile, count, "gold").draw(&self.ctx);
+        }
+        if let (Some(focus), None) = (self.focus, &self.playback) {
+            self.focus_ring(focus).draw(&self.ctx);
+        }
+
+        // Draw the dragged cards on top
+        if let Some(drag) = &self.dragging_card {
+            let cards = self.game.pile(drag.source);
+            for (i, card) in cards[cards.len() - drag.count..].iter().enumerate() {
+                self.draw_card(
+                    card,
+                    drag.x - drag.offset_x,
+                    drag.y - drag.offset_y + i as f64 * DRAG_OVERLAP,
                 );
             }
         }
 
-        // Render stock pile
-        if !self.stock.is_empty() {
-            if let Some(card) = self.stock.last_mut() {
-                card.x = PILE_GAP;
-                card.y = PILE_GAP;
-                card.draw(&self.ctx, &self.card_images);
+        self.overlay.draw(&self.ctx, js_sys::Date::now());
+    }
+
+    // Fills the window with the canvas and fits the board to it again, on
+    // start, whenever the window or its pixel ratio changes and when the
+    // statistics screen opens or closes
+    fn resize(&mut self) {
+        let window = window().unwrap();
+        let size = |size: Result<JsValue, JsValue>| size.ok().and_then(|size| size.as_f64());
+        let css_width = size(window.inner_width()).unwrap_or(self.panel.board.0);
+        let css_height = size(window.inner_height()).unwrap_or(self.panel.board.1);
+        self.panel = Panel::fit(self.game.rules.variant, (css_width, css_height));
+
+        // The statistics table is wider than the piles of a tall window
+        let mut board = self.panel.board;
+        if self.showing_statistics {
+            board.0 = board.0.max(2.0 * STATISTICS_ORIGIN.0 + statistics::SIZE.0);
+            board.1 = board.1.max(2.0 * STATISTICS_ORIGIN.1 + statistics::SIZE.1);
+        }
+        self.layout = Layout::fit(css_width, css_height, window.device_pixel_ratio(), board);
+
+        let style = self.canvas.style();
+        let _ = style.set_property("width", &format!("{}px", css_width));
+        let _ = style.set_property("height", &format!("{}px", css_height));
+        self.canvas.set_width(self.layout.width);
+        self.canvas.set_height(self.layout.height);
+        self.render();
+    }
+
+    fn elapsed_seconds(&self) -> u64 {
+        let now = self.finished_at.unwrap_or_else(js_sys::Date::now);
+        ((now - self.started_at) / 1000.0) as u64
+    }
+
+    // Called every second to keep the clock on the HUD running; this also
+    // takes expired hints off the board and brings the board back after
+    // the win message
+    fn tick(&mut self) {
+        match self.finished_at {
+            None => self.render(),
+            Some(finished_at) if self.playback.is_none() => {
+                if js_sys::Date::now() - finished_at > WIN_SCREEN_MS {
+                    self.render();
+                }
             }
-        } else {
-            // Draw empty stock pile placeholder
-            self.ctx.set_stroke_style(&JsValue::from_str("black"));
-            self.ctx.set_line_width(2.0);
+            Some(_) => {}
+        }
+    }
+
+    // Text lines below the toolbar
+    fn draw_hud(&self) {
+        let mut lines = Vec::new();
+        if let Some(seed) = self.game.seed {
+            lines.push(format!("Deal #{}", seed));
+        }
+        match &self.vegas_bank {
+            Some(bank) => {
+                lines.push(format!("Vegas: {}", format_money(self.game.score())));
+                lines.push(format!("Bank: {}", format_money(bank.balance(&self.game))));
+            }
+            None => lines.push(format!("Score: {}", self.game.score())),
+        }
+        lines.push(format!("Time: {}", format_time(self.elapsed_seconds())));
+        match self.game.rules.variant {
+            Variant::Klondike => lines.push(format!("Draw {}", self.game.rules.draw_count)),
+            Variant::Spider { suits } => {
+                let noun = if suits == 1 { "suit" } else { "suits" };
+                lines.push(format!("Spider, {} {}", suits, noun));
+                let deals = self.game.stock.len() / self.game.tableau.len();
+                lines.push(format!("Deals left: {}", deals));
+            }
+        }
+        if let PassLimit::Passes(passes) = self.game.rules.pass_limit {
+            let pass = (self.game.recycles() + 1).min(passes);
+            lines.push(format!("Pass {} of {}", pass, passes));
+        }
+
+        let (x, y) = self.panel.hud;
+        self.ctx.set_font("28px Arial");
+        self.ctx.set_fill_style_str("white");
+        for (i, line) in lines.iter().enumerate() {
             self.ctx
-                .stroke_rect(PILE_GAP, PILE_GAP, CARD_WIDTH, CARD_HEIGHT);
+                .fill_text(line, x, y + 28.0 + i as f64 * HUD_LINE_HEIGHT)
+                .unwrap();
+        }
+    }
+
+    // Picks up the bank left by earlier games in Vegas mode
+    fn open_vegas_bank(&mut self, resumed: bool) {
+        if self.game.rules.scoring == Scoring::Vegas {
+            let mut balance = storage::load(VEGAS_BANK_KEY)
+                .and_then(|balance| balance.parse().ok())
+                .unwrap_or(0);
+            // The stored balance already counts a resumed game
+            if resumed {
+                balance -= self.game.score();
+            }
+            self.vegas_bank = Some(VegasBank::new(balance));
+            self.save_vegas_bank();
         }
+    }
 
-        // Render discard pile
-        if let Some(card) = self.discard.last_mut() {
-            card.x = PILE_GAP + CARD_WIDTH + PILE_GAP;
-            card.y = PILE_GAP;
-            card.draw(&self.ctx, &self.card_images);
-        } else {
-            // Draw empty discard pile placeholder
-            self.ctx.set_stroke_style(&JsValue::from_str("black"));
-            self.ctx.set_line_width(2.0);
-            self.ctx.stroke_rect(
-                PILE_GAP + CARD_WIDTH + PILE_GAP,
-                PILE_GAP,
-                CARD_WIDTH,
-                CARD_HEIGHT,
-            );
+    // The stored balance always includes the game in progress, so leaving
+    // the page mid-game still costs the buy-in
+    fn save_vegas_bank(&self) {
+        if let Some(bank) = &self.vegas_bank {
+            storage::save(VEGAS_BANK_KEY, &bank.balance(&self.game).to_string());
         }
     }
 
-    fn handle_stock_click(&mut self) {
-        if let Some(mut card) = self.stock.pop() {
-            // Flip the top card and move it to the discard pile
-            card.face_up = true;
-            self.discard.push(card); // Add card to the discard pile
-            self.render();
-        } else if !self.discard.is_empty() {
-            // Recycle the discard pile back into the stock pile
-            while let Some(mut card) = self.discard.pop() {
-                card.face_up = false; // Flip the card face-down
-                self.stock.push(card); // Move to the stock pile
-            }
-            self.render(); // Ensure proper rendering
-        }
-    }
-
-    fn handle_mousedown(&mut self, x: f64, y: f64) {
-        // Check the foundation piles
-        for (pile_idx, pile) in self.foundation.iter_mut().enumerate() {
-            if let Some(card) = pile.last() {
-                let foundation_x =
-                    PILE_GAP + 4.5 * CARD_WIDTH + (pile_idx as f64 * (CARD_WIDTH + PILE_GAP));
-                let foundation_y = PILE_GAP;
-                if x >= foundation_x
-                    && x <= foundation_x + CARD_WIDTH
-                    && y >= foundation_y
-                    && y <= foundation_y + CARD_HEIGHT
-                {
-                    // Drag the card from the foundation pile
-                    self.dragging_card =
-                        Some((vec![card.clone()], x - card.x, y - card.y, pile_idx, 2)); // 2 indicates foundation pile
-                    pile.pop(); // Remove the card from the foundation pile
-                    self.render();
+    // Bookkeeping after every change to the board
+    fn board_changed(&mut self) {
+        self.save_vegas_bank();
+        self.selected_card = None;
+        self.overlay.clear();
+        self.render();
+
+        // Check for a win after every move
+        if self.finished_at.is_none() && self.game.check_game_won() {
+            self.finished_at = Some(js_sys::Date::now());
+            self.record_win();
+            self.celebrate_win(); // Trigger the win animation
+        }
+        self.save_game();
+    }
+
+    // Only dealt games count: an imported position may be one move from
+    // the end
+    fn record_win(&mut self) {
+        if self.game.seed.is_none() {
+            return;
+        }
+        let seconds = self.elapsed_seconds();
+        let final_score = self.game.score() + self.time_bonus(seconds).unwrap_or(0);
+        self.statistics.record_win(&self.game, seconds, final_score);
+        save_statistics(&self.statistics);
+    }
+
+    // Klondike rewards a quick win; Spider's score already counts every move
+    fn time_bonus(&self, seconds: u64) -> Option<i32> {
+        match self.game.rules.variant {
+            Variant::Klondike => Some(score::time_bonus(seconds)),
+            Variant::Spider { .. } => None,
+        }
+    }
+
+    // A dealt game left for another after at least one move counts as lost
+    fn record_abandoned_game(&mut self) {
+        if self.finished_at.is_none() && self.game.seed.is_some() && self.game.can_undo() {
+            self.statistics.record_loss(&self.game.rules);
+            save_statistics(&self.statistics);
+        }
+    }
+
+    fn handle_statistics_press(&mut self, x: f64, y: f64) {
+        match statistics::control_at(STATISTICS_ORIGIN, x, y) {
+            Some(StatisticsControl::Reset) => {
+                let confirmed = window()
+                    .unwrap()
+                    .confirm_with_message("Reset all statistics?")
+                    .unwrap_or(false);
+                if !confirmed {
                     return;
                 }
+                self.statistics = Statistics::default();
+                storage::remove(STATISTICS_KEY);
+            }
+            Some(StatisticsControl::Close) => {
+                self.showing_statistics = false;
+                return self.resize();
             }
+            None => return,
         }
+        self.render();
+    }
 
-        // Check tableau piles
-        for (pile_idx, pile) in self.tableau.iter_mut().enumerate() {
-            if let Some(card_idx) = pile
-                .iter()
-                .rposition(|card| card.face_up && card.contains(x, y))
-            {
-                let cards_to_drag = pile.split_off(card_idx);
-                let offset_x = x - cards_to_drag[0].x;
-                let offset_y = y - cards_to_drag[0].y;
-                self.dragging_card = Some((cards_to_drag, offset_x, offset_y, pile_idx, 0));
-                self.render();
-                return;
+    // Keeps the game in progress for the next page load; a won game has
+    // nothing left to resume
+    fn save_game(&self) {
+        let saved = match self.finished_at {
+            Some(_) => None,
+            // Imported positions have no deal to replay, so they are not kept
+            None => {
+                let log = self.log.as_ref().map_or(&[][..], |log| log.entries());
+                SavedGame::new(&self.game, self.elapsed_seconds(), log)
             }
+        };
+        match saved {
+            Some(saved) => storage::save(SAVED_GAME_KEY, &saved.encode()),
+            None => storage::remove(SAVED_GAME_KEY),
         }
+    }
 
-        // Check the stock pile (whether it has cards or is empty)
-        if x >= PILE_GAP
-            && x <= PILE_GAP + CARD_WIDTH
-            && y >= PILE_GAP
-            && y <= PILE_GAP + CARD_HEIGHT
-        {
-            self.handle_stock_click();
-            return;
+    // Every change the player makes goes through here, so it is logged
+    fn act(&mut self, action: Action) -> bool {
+        let at = (js_sys::Date::now() - self.started_at) as u64;
+        match &mut self.log {
+            Some(log) => log.record(&mut self.game, action, at),
+            None => action.perform(&mut self.game),
         }
+    }
 
-        // Check the discard pile
-        if let Some(card) = self.discard.last_mut() {
-            let discard_x = PILE_GAP + CARD_WIDTH + PILE_GAP;
-            let discard_y = PILE_GAP;
-            if x >= discard_x
-                && x <= discard_x + CARD_WIDTH
-                && y >= discard_y
-                && y <= discard_y + CARD_HEIGHT
-            {
-                // Correct the card's position
-                card.x = discard_x;
-                card.y = discard_y;
-
-                self.dragging_card = Some((vec![card.clone()], x - card.x, y - card.y, 0, 1)); // Store drag info
-                self.discard.pop(); // Remove the card from the discard pile
+    fn handle_stock_click(&mut self) {
+        match self.game.stock_move() {
+            Some(mv) => {
+                self.act(Action::Play(mv));
+                self.board_changed();
+            }
+            // Spider only deals with every column filled: point out the
+            // empty ones
+            None if self.is_spider() && !self.game.stock.is_empty() => {
+                let highlights = (0..self.game.tableau.len())
+                    .map(Pile::Tableau)
+                    .filter(|&pile| self.game.pile(pile).is_empty())
+                    .map(|pile| self.cards_highlight(pile, 0, "red"))
+                    .collect();
+                self.overlay
+                    .show(highlights, js_sys::Date::now(), HINT_DURATION_MS);
                 self.render();
-                return;
             }
+            None => {}
+        }
+    }
+
+    fn handle_button(&mut self, button: Button) {
+        match button {
+            Button::NewGame => self.new_game(),
+            Button::Hint => self.show_hint(),
+            Button::Undo => self.undo(),
+            Button::Redo => self.redo(),
+            Button::Export => self.export_position(),
+            Button::ExportDeal => self.export_deal(),
+            Button::Import => self.import_position(),
+            Button::Replay => self.start_replay(),
+            Button::Statistics => {
+                self.showing_statistics = true;
+                self.resize();
+            }
+        }
+    }
+
+    // Watches the finished game again from the deal
+    fn start_replay(&mut self) {
+        if self.finished_at.is_none() || self.playback.is_some() {
+            return;
+        }
+        if let Some(log) = &self.log {
+            let start = log.replay(0);
+            let game = std::mem::replace(&mut self.game, start);
+            self.playback = Some(Playback::new(log.clone(), game, js_sys::Date::now()));
+            self.render();
         }
     }
 
-    fn handle_mousemove(&mut self, x: f64, y: f64) {
-        let mut cloned_cards_to_draw = None;
+    // Puts the finished game back on the board
+    fn stop_replay(&mut self) {
+        if let Some(playback) = self.playback.take() {
+            self.game = playback.game;
+            self.render();
+        }
+    }
 
-        if let Some((cards, offset_x, offset_y, _, _)) = &mut self.dragging_card {
-            // Update the dragged cards
-            for (i, card) in cards.iter_mut().enumerate() {
-                card.x = x - *offset_x;
-                card.y = y - *offset_y + i as f64 * 30.0;
+    // Called on a timer while a replay runs
+    fn advance_replay(&mut self) {
+        let changed = match &mut self.playback {
+            Some(playback) => playback.advance(js_sys::Date::now()),
+            None => return,
+        };
+        if changed {
+            self.show_replay_step();
+        }
+    }
+
+    // Deals again and replays the log up to the current step
+    fn show_replay_step(&mut self) {
+        if let Some(playback) = &self.playback {
+            self.game = playback.log.replay(playback.step);
+            self.render();
+        }
+    }
+
+    fn handle_playback_press(&mut self, x: f64, y: f64) {
+        let origin = self.panel.playback;
+        let playback = match &mut self.playback {
+            Some(playback) => playback,
+            None => return,
+        };
+        if playback.bar_contains(origin, x, y) {
+            playback.scrubbing = true;
+            playback.seek(origin, x);
+        } else {
+            match playback.control_at(origin, x, y) {
+                Some(Control::Back) => playback.step_by(false),
+                Some(Control::PlayPause) => playback.toggle_playing(),
+                Some(Control::Forward) => playback.step_by(true),
+                Some(Control::Speed) => playback.next_speed(),
+                Some(Control::Close) => return self.stop_replay(),
+                None => return,
             }
+        }
+        self.show_replay_step();
+    }
+
+    // Deals a fresh game with the same rules
+    fn new_game(&mut self) {
+        self.start_game(Game::new(random_seed(), self.game.rules));
+    }
+
+    // Replaces the game on the board, carrying the Vegas bank over
+    fn start_game(&mut self, game: Game) {
+        self.record_abandoned_game();
+        if let Some(bank) = &mut self.vegas_bank {
+            bank.settle(&self.game);
+        }
+        let variant_changed = game.rules.variant != self.game.rules.variant;
+        self.game = game;
+        if variant_changed {
+            self.resize();
+        }
+        match self.game.rules.scoring {
+            Scoring::Vegas if self.vegas_bank.is_none() => self.open_vegas_bank(false),
+            Scoring::Vegas => {}
+            Scoring::Standard => self.vegas_bank = None,
+        }
+        self.dragging_card = None;
+        self.auto_completing = false;
+        self.log = MoveLog::new(&self.game);
+        self.started_at = js_sys::Date::now();
+        self.finished_at = None;
+        self.board_changed();
+    }
+
+    // Copies the position to the clipboard, e.g. for a bug report
+    fn export_position(&self) {
+        copy_to_clipboard(&position::encode(&self.game));
+    }
+
+    // Copies the deal this game started from, listed for other solvers
+    fn export_deal(&self) {
+        match notation::export_deal(&self.game) {
+            Some(text) => copy_to_clipboard(&text),
+            None => alert("This game did not start from a fresh Klondike deal."),
+        }
+    }
 
-            // Clone their current state into a local variable
-            cloned_cards_to_draw = Some(cards.clone());
+    // Asks for a position or deal listing copied earlier and plays it
+    fn import_position(&mut self) {
+        let text = match window()
+            .unwrap()
+            .prompt_with_message("Paste a position or deal:")
+        {
+            Ok(Some(text)) if !text.trim().is_empty() => text,
+            _ => return,
+        };
+        let game = if text.contains("Talon:") {
+            notation::import_deal(&text, self.game.rules).map_err(|error| error.to_string())
+        } else {
+            position::decode(&text).map_err(|error| error.to_string())
+        };
+        match game {
+            Ok(game) => self.start_game(game),
+            Err(error) => alert(&format!("Could not read the position: {}", error)),
         }
+    }
 
+    // Outlines the source and destination of the suggested move for a moment
+    fn show_hint(&mut self) {
+        if self.finished_at.is_some() {
+            return;
+        }
+        let mv = match Solver::new(HINT_NODE_BUDGET).hint(&self.game) {
+            Some(mv) => mv,
+            None => return,
+        };
+
+        let highlights = match mv.transfer() {
+            Some((from, count, to)) => vec![
+                self.cards_highlight(from, count, "gold"),
+                self.cards_highlight(to, 1, "deepskyblue"),
+            ],
+            // Draws, recycles and deals are a click on the stock
+            None => vec![self.cards_highlight(Pile::Stock, 1, "gold")],
+        };
+        self.overlay
+            .show(highlights, js_sys::Date::now(), HINT_DURATION_MS);
         self.render();
+    }
+
+    // Outline around the top `count` cards of a pile, or its empty slot
+    fn cards_highlight(&self, pile: Pile, count: usize, color: &'static str) -> Highlight {
+        let (x, y, height) = self.cards_area(pile, count);
+        Highlight {
+            x,
+            y,
+            width: CARD_WIDTH,
+            height,
+            color,
+        }
+    }
 
-        // Draw the cloned cards on top
-        if let Some(cards_to_draw) = cloned_cards_to_draw {
-            for card in cards_to_draw.iter() {
-                card.draw(&self.ctx, &self.card_images);
+    // Drawn just outside the cards so a selection under the cursor still
+    // shows
+    fn focus_ring(&self, focus: Focus) -> Highlight {
+        let count = focus.count(&self.game);
+        let ring = self.cards_highlight(focus.pile, count, "deepskyblue");
+        Highlight {
+            x: ring.x - 8.0,
+            y: ring.y - 8.0,
+            width: ring.width + 16.0,
+            height: ring.height + 16.0,
+            ..ring
+        }
+    }
+
+    // Top-left corner and height of the top `count` cards of a pile, or of
+    // its empty slot
+    fn cards_area(&self, pile: Pile, count: usize) -> (f64, f64, f64) {
+        let len = self.game.pile(pile).len();
+        let (x, y) = match pile {
+            Pile::Tableau(_) => self.pile_position(pile, len.saturating_sub(count)),
+            Pile::Discard => self.discard_top_position(),
+            _ => self.pile_position(pile, 0),
+        };
+        let height = match pile {
+            Pile::Tableau(_) => {
+                CARD_HEIGHT + count.min(len).saturating_sub(1) as f64 * TABLEAU_OVERLAP
             }
+            _ => CARD_HEIGHT,
+        };
+        (x, y, height)
+    }
+
+    fn undo(&mut self) {
+        self.dragging_card = None;
+        if self.act(Action::Undo) {
+            self.board_changed();
         }
     }
 
-    fn handle_mouseup(&mut self, x: f64, y: f64) {
-        if let Some((mut cards, _, _, source_pile_idx, source_pile_type)) =
-            self.dragging_card.take()
+    fn redo(&mut self) {
+        self.dragging_card = None;
+        if self.act(Action::Redo) {
+            self.board_changed();
+        }
+    }
+
+    // Called on a timer; sends one card up per call while auto-completing
+    fn step_auto_complete(&mut self) {
+        if !self.auto_completing {
+            return;
+        }
+        match self.game.auto_complete_move() {
+            Some(mv) => {
+                self.act(Action::Play(mv));
+                self.board_changed();
+            }
+            None => self.auto_completing = false,
+        }
+    }
+
+    fn handle_keydown(&mut self, event: &KeyboardEvent) {
+        // Keys wait until a drag is dropped, since a move under the dragged
+        // cards would leave the drag pointing at cards that have gone
+        if self.auto_completing
+            || self.playback.is_some()
+            || self.showing_statistics
+            || self.dragging_card.is_some()
         {
-            let valid_drop = if cards.len() == 1 {
-                self.try_drop_card(&cards[0], x, y) // Check for a valid drop of a single card
-            } else {
-                self.try_drop_stack(&cards, x, y) // Check for a valid drop of a stack
-            };
-
-            if !valid_drop {
-                // Return the cards to their original pile if the drop is invalid
-                match source_pile_type {
-                    0 => self.tableau[source_pile_idx].extend(cards), // Tableau
-                    1 => self.discard.push(cards.pop().unwrap()),     // Discard
-                    2 => self.foundation[source_pile_idx].push(cards.pop().unwrap()), // Foundation
-                    _ => {}
+            return;
+        }
+        if event.ctrl_key() || event.meta_key() {
+            match event.key().to_lowercase().as_str() {
+                "z" if event.shift_key() => self.redo(),
+                "z" => self.undo(),
+                "y" => self.redo(),
+                _ => return,
+            }
+        } else {
+            match event.key().as_str() {
+                "ArrowLeft" => self.move_focus(Focus::left),
+                "ArrowRight" => self.move_focus(Focus::right),
+                "ArrowUp" => self.move_focus(Focus::up),
+                "ArrowDown" => self.move_focus(Focus::down),
+                "Enter" | " " => self.pick_up_or_drop(),
+                "Escape" => {
+                    self.selected_card = None;
+                    self.render();
                 }
+                "d" | "D" => self.handle_stock_click(),
+                "f" | "F" => self.send_focus_to_foundation(),
+                "u" | "U" => self.undo(),
+                _ => return,
             }
+        }
+        event.prevent_default();
+    }
 
-            // Turn the last card in the tableau face-up if it's not already
-            if source_pile_type == 0 && !self.tableau[source_pile_idx].is_empty() {
-                if let Some(last_card) = self.tableau[source_pile_idx].last_mut() {
-                    last_card.face_up = true;
+    // The first arrow key only brings the cursor up, on the first column
+    fn move_focus(&mut self, step: fn(Focus, &Game) -> Focus) {
+        self.focus = Some(match self.focus {
+            Some(focus) => step(focus, &self.game),
+            None => Focus::on_top(&self.game, Pile::Tableau(0)),
+        });
+        self.render();
+    }
+
+    // Picks up the cards from the cursor to the top of the pile, or puts the
+    // cards already picked up on the pile under the cursor
+    fn pick_up_or_drop(&mut self) {
+        let focus = match self.focus {
+            Some(focus) => focus.settle(&self.game),
+            None => return,
+        };
+        match self.selected_card {
+            // Putting the cards back where they came from
+            Some((source, _)) if source == focus.pile => self.selected_card = None,
+            Some((source, count)) => {
+                let mv =
+                    Move::between(source, count, focus.pile).filter(|&mv| self.game.is_legal(mv));
+                // Cards stay picked up after an illegal drop, to try elsewhere
+                if let Some(mv) = mv {
+                    // The cursor follows the cards onto their new top
+                    let len = self.game.pile(focus.pile).len();
+                    self.focus = Some(Focus {
+                        pile: focus.pile,
+                        index: len + count - 1,
+                    });
+                    return self.play(mv);
                 }
             }
+            None if focus.pile == Pile::Stock => return self.handle_stock_click(),
+            None => match focus.count(&self.game) {
+                0 => return,
+                count => self.selected_card = Some((focus.pile, count)),
+            },
+        }
+        self.render();
+    }
 
-            self.render();
+    fn send_focus_to_foundation(&mut self) {
+        let source = match self.focus {
+            Some(focus) => focus.pile,
+            None => return,
+        };
+        let mv = (0..self.game.foundation.len())
+            .filter_map(|i| Move::between(source, 1, Pile::Foundation(i)))
+            .find(|&mv| self.game.is_legal(mv));
+        if let Some(mv) = mv {
+            self.play(mv);
+        }
+    }
 
-            // Check for a win after every move
-            if self.check_game_won() {
-                self.celebrate_win(); // Trigger the win animation
-            }
-        }
-    }
-
-    fn try_drop_card(&mut self, card: &Card, x: f64, y: f64) -> bool {
-        // Combine foundation and tableau piles into a unified list with an indicator for pile type
-        let mut all_piles: Vec<(&mut Vec<Card>, bool)> = self
-            .foundation
-            .iter_mut()
-            .map(|pile| (pile, true)) // `true` indicates foundation pile
-            .chain(self.tableau.iter_mut().map(|pile| (pile, false))) // `false` indicates tableau pile
-            .collect();
-
-        // Check each pile for a valid drop
-        for (pile, is_foundation) in all_piles.iter_mut() {
-            if let Some(target) = pile.last() {
-                if target.contains(x, y) {
-                    if *is_foundation {
-                        // Check foundation rules
-                        if Self::is_valid_foundation_move(card, target) {
-                            pile.push(card.clone());
-                            return true;
-                        }
-                    } else {
-                        // Check tableau rules
-                        if Self::is_valid_tableau_move(card, target) {
-                            pile.push(card.clone());
-                            return true;
-                        }
-                    }
+    fn start_drag(&mut self, source: Pile, count: usize, x: f64, y: f64) {
+        let len = self.game.pile(source).len();
+        let (left, top) = match source {
+            Pile::Discard => self.discard_top_position(),
+            _ => self.pile_position(source, len - count),
+        };
+        self.dragging_card = Some(Drag {
+            source,
+            count,
+            offset_x: x - left,
+            offset_y: y - top,
+            start_x: x,
+            start_y: y,
+            x,
+            y,
+        });
+        self.render();
+    }
+
+    fn handle_press(&mut self, x: f64, y: f64) {
+        // The board belongs to the auto-complete until it is done, and only
+        // the replay controls work during a replay
+        if self.auto_completing {
+            return;
+        }
+        // The keyboard cursor goes away until the next arrow key
+        self.focus = None;
+        if self.playback.is_some() {
+            self.handle_playback_press(x, y);
+            return;
+        }
+        if self.showing_statistics {
+            self.handle_statistics_press(x, y);
+            return;
+        }
+
+        if let Some(button) = Button::ALL
+            .iter()
+            .copied()
+            .find(|button| button.contains(self.panel.toolbar, x, y))
+        {
+            self.handle_button(button);
+            return;
+        }
+
+        // With cards selected, a click on another pile tries to move them there
+        if let Some((source, count)) = self.selected_card.take() {
+            match self.pile_at(x, y) {
+                // Clicking the selection again puts it back down
+                Some(pile) if pile == source => {
+                    self.render();
+                    return;
                 }
-            } else if pile.is_empty() {
-                if *is_foundation {
-                    // Allow only Aces to start a foundation pile
-                    if card.rank == "A" {
-                        pile.push(card.clone());
-                        return true;
-                    }
-                } else {
-                    // Allow only Kings to start an empty tableau pile
-                    if card.rank == "K" {
-                        pile.push(card.clone());
-                        return true;
+                Some(pile) => {
+                    let mv =
+                        Move::between(source, count, pile).filter(|&mv| self.game.is_legal(mv));
+                    if let Some(mv) = mv {
+                        self.play(mv);
+                        return;
                     }
                 }
+                None => {}
+            }
+            self.render();
+        }
+
+        // Check the foundation piles; finished Spider runs stay put
+        for pile_idx in 0..self.game.foundation.len() {
+            let pile = Pile::Foundation(pile_idx);
+            let (left, top) = self.pile_position(pile, 0);
+            let movable = !self.game.pile(pile).is_empty() && !self.is_spider();
+            if movable && contains(left, top, CARD_HEIGHT, x, y) {
+                // Drag the card from the foundation pile
+                self.start_drag(pile, 1, x, y);
+                return;
+            }
+        }
+
+        // Check tableau piles
+        for pile_idx in 0..self.game.tableau.len() {
+            let pile = Pile::Tableau(pile_idx);
+            let cards = self.game.pile(pile);
+            let length = cards.len();
+            let hit = cards.iter().enumerate().rposition(|(j, card)| {
+                let (left, top) = self.pile_position(pile, j);
+                // Only the topmost card is fully visible, the others show their top strip
+                let height = if j == length - 1 {
+                    CARD_HEIGHT
+                } else {
+                    TABLEAU_OVERLAP
+                };
+                card.face_up && contains(left, top, height, x, y)
+            });
+            if let Some(card_idx) = hit {
+                self.start_drag(pile, length - card_idx, x, y);
+                return;
             }
         }
 
-        return false; // Return false if no valid drop is found
+        // Check the stock pile (whether it has cards or is empty)
+        let (left, top) = self.pile_position(Pile::Stock, 0);
+        if contains(left, top, CARD_HEIGHT, x, y) {
+            self.handle_stock_click();
+            return;
+        }
+
+        // Check the discard pile
+        let (left, top) = self.discard_top_position();
+        if !self.game.discard.is_empty() && contains(left, top, CARD_HEIGHT, x, y) {
+            self.start_drag(Pile::Discard, 1, x, y);
+        }
     }
 
-    fn try_drop_stack(&mut self, cards: &[Card], x: f64, y: f64) -> bool {
-        for pile in self.tableau.iter_mut() {
-            if let Some(target) = pile.last() {
-                if target.contains(x, y) && Self::is_valid_tableau_move(&cards[0], target) {
-                    pile.extend_from_slice(cards);
-                    return true;
-                }
-            } else if pile.is_empty() && cards[0].rank == "K" {
-                // Only Kings can be placed in empty tableau piles
-                pile.extend_from_slice(cards);
-                return true;
+    fn handle_move(&mut self, x: f64, y: f64) {
+        if let Some(playback) = &mut self.playback {
+            if playback.scrubbing {
+                playback.seek(self.panel.playback, x);
+                self.show_replay_step();
             }
+            return;
+        }
+        if let Some(drag) = &mut self.dragging_card {
+            drag.x = x;
+            drag.y = y;
+            self.render();
+        }
+    }
+
+    fn handle_release(&mut self, x: f64, y: f64) {
+        if let Some(playback) = &mut self.playback {
+            playback.scrubbing = false;
         }
+        if let Some(mut drag) = self.dragging_card.take() {
+            drag.x = x;
+            drag.y = y;
+            // Pressing and releasing in place selects the cards instead
+            let travel = (x - drag.start_x).hypot(y - drag.start_y);
+            let slop = self.press.map_or(CLICK_SLOP, |press| press.slop);
+            if travel < slop {
+                self.selected_card = Some((drag.source, drag.count));
+                self.render();
+                return;
+            }
 
-        return false;
+            match self.drop_target(&drag) {
+                Some(mv) => self.play(mv),
+                // Invalid drops leave the cards where they were
+                None => self.render(),
+            }
+        }
     }
 
-    // Helper function for checking if a card is red or black
-    fn is_red(card: &Card) -> bool {
-        return card.suit == "hearts" || card.suit == "diamonds";
+    // Sends the double-clicked card to a foundation, or else to the best
+    // tableau pile that takes it
+    fn handle_dblclick(&mut self, x: f64, y: f64) {
+        if self.auto_completing || self.playback.is_some() || self.showing_statistics {
+            return;
+        }
+        if let Some(source) = self.top_card_at(x, y) {
+            self.send_on(source);
+        }
     }
 
-    fn is_valid_tableau_move(card: &Card, target: &Card) -> bool {
-        let rank_order = vec![
-            "A", "2", "3", "4", "5", "6", "7", "8", "9", "10", "J", "Q", "K",
-        ];
-        let card_index = rank_order.iter().position(|&r| r == card.rank).unwrap();
-        let target_index = rank_order.iter().position(|&r| r == target.rank).unwrap();
+    // Plays the top card of `source` to a foundation, or else to the best
+    // tableau pile that takes it; false if nothing does
+    fn send_on(&mut self, source: Pile) -> bool {
+        let foundations = (0..self.game.foundation.len()).map(Pile::Foundation);
+        let tableaus = (0..self.game.tableau.len()).map(Pile::Tableau);
+        // Building on a card keeps empty columns free for kings
+        let (empty, occupied): (Vec<Pile>, Vec<Pile>) =
+            tableaus.partition(|&pile| self.game.pile(pile).is_empty());
+        let target = foundations
+            .chain(occupied)
+            .chain(empty)
+            .filter_map(|pile| Move::between(source, 1, pile))
+            .find(|&mv| self.game.is_legal(mv));
+        match target {
+            Some(mv) => {
+                self.play(mv);
+                true
+            }
+            None => false,
+        }
+    }
 
-        return card_index + 1 == target_index && Self::is_red(card) != Self::is_red(target);
+    // Starts a press unless another pointer is down already, so a second
+    // finger cannot grab or drop cards. Returns the press number when a
+    // long-press timer should be set, which is for touch and pens only.
+    fn handle_pointerdown(&mut self, event: &PointerEvent) -> Option<u32> {
+        if self.press.is_some() || !event.is_primary() {
+            return None;
+        }
+        let touch = event.pointer_type() != "mouse";
+        let slop = if touch { TAP_SLOP } else { CLICK_SLOP };
+        self.presses += 1;
+        self.press = Some(Press {
+            pointer_id: event.pointer_id(),
+            number: self.presses,
+            slop: slop * self.layout.board_units_per_css_pixel(),
+        });
+        // Keeps the moves and the release coming while the pointer is off
+        // the canvas
+        let _ = self.canvas.set_pointer_capture(event.pointer_id());
+
+        let (x, y) = self
+            .layout
+            .board_point(pointer::css_point(&self.canvas, event));
+        self.handle_press(x, y);
+        if touch {
+            Some(self.presses)
+        } else {
+            None
+        }
     }
 
-    fn is_valid_foundation_move(card: &Card, target: &Card) -> bool {
-        let rank_order = vec![
-            "A", "2", "3", "4", "5", "6", "7", "8", "9", "10", "J", "Q", "K",
-        ];
-        let card_index = rank_order.iter().position(|&r| r == card.rank).unwrap();
-        let target_index = rank_order.iter().position(|&r| r == target.rank).unwrap();
+    fn handle_pointermove(&mut self, event: &PointerEvent) {
+        if self.is_pressing(event) {
+            let (x, y) = self
+                .layout
+                .board_point(pointer::css_point(&self.canvas, event));
+            self.handle_move(x, y);
+        }
+    }
 
-        // Ensure the card is the next in the sequence and matches the same suit
-        return card_index == target_index + 1 && card.suit == target.suit;
+    fn handle_pointerup(&mut self, event: &PointerEvent) {
+        if self.is_pressing(event) {
+            let (x, y) = self
+                .layout
+                .board_point(pointer::css_point(&self.canvas, event));
+            self.handle_release(x, y);
+            self.press = None;
+        }
     }
 
-    fn check_game_won(&self) -> bool {
-        // Check if all cards are in the foundation piles
-        return self.foundation.iter().all(|pile| pile.len() == 13); // 13 cards per foundation pile
+    // The browser took the pointer over, e.g. for scrolling: put any cards
+    // being dragged back
+    fn handle_pointercancel(&mut self, event: &PointerEvent) {
+        if self.is_pressing(event) {
+            self.press = None;
+            self.dragging_card = None;
+            if let Some(playback) = &mut self.playback {
+                playback.scrubbing = false;
+            }
+            self.render();
+        }
+    }
+
+    fn is_pressing(&self, event: &PointerEvent) -> bool {
+        matches!(self.press, Some(press) if press.pointer_id == event.pointer_id())
+    }
+
+    // A finger held still on a card sends it on, as a double-click does;
+    // `number` is the press the timer was set for
+    fn handle_long_press(&mut self, number: u32) {
+        let press = match self.press {
+            Some(press) if press.number == number => press,
+            _ => return,
+        };
+        let drag = match &self.dragging_card {
+            Some(drag) => drag,
+            None => return,
+        };
+        let travel = (drag.x - drag.start_x).hypot(drag.y - drag.start_y);
+        let single = drag.count == 1 && matches!(drag.source, Pile::Tableau(_) | Pile::Discard);
+        if travel >= press.slop || !single {
+            return;
+        }
+
+        let source = drag.source;
+        self.dragging_card = None;
+        if !self.send_on(source) {
+            self.render();
+        }
+    }
+
+    // Foundation or tableau pile whose cards or empty slot are under the pointer
+    fn pile_at(&self, x: f64, y: f64) -> Option<Pile> {
+        let foundations = (0..self.game.foundation.len()).map(Pile::Foundation);
+        let tableaus = (0..self.game.tableau.len()).map(Pile::Tableau);
+        foundations.chain(tableaus).find(|&pile| {
+            let (left, top, height) = self.cards_area(pile, self.game.pile(pile).len());
+            contains(left, top, height, x, y)
+        })
+    }
+
+    // Tableau or discard pile whose face-up top card is under the pointer
+    fn top_card_at(&self, x: f64, y: f64) -> Option<Pile> {
+        let (left, top) = self.discard_top_position();
+        if !self.game.discard.is_empty() && contains(left, top, CARD_HEIGHT, x, y) {
+            return Some(Pile::Discard);
+        }
+        (0..self.game.tableau.len())
+            .map(Pile::Tableau)
+            .find(|&pile| {
+                let cards = self.game.pile(pile);
+                match cards.last() {
+                    Some(card) if card.face_up => {
+                        let (left, top) = self.pile_position(pile, cards.len() - 1);
+                        contains(left, top, CARD_HEIGHT, x, y)
+                    }
+                    _ => false,
+                }
+            })
+    }
+
+    // Applies a move made by the player, which may leave nothing to decide
+    fn play(&mut self, mv: Move) {
+        if self.act(Action::Play(mv)) {
+            self.board_changed();
+            self.auto_completing = self.game.can_auto_complete();
+        }
+    }
+
+    // The legal target whose drop zone the leading dragged card overlaps
+    // most, so a drop near a pile snaps onto it
+    fn drop_target(&self, drag: &Drag) -> Option<Move> {
+        let left = drag.x - drag.offset_x;
+        let top = drag.y - drag.offset_y;
+        let foundations = (0..self.game.foundation.len()).map(Pile::Foundation);
+        let tableaus = (0..self.game.tableau.len()).map(Pile::Tableau);
+
+        foundations
+            .chain(tableaus)
+            .filter_map(|pile| {
+                let mv = Move::between(drag.source, drag.count, pile)
+                    .filter(|&mv| self.game.is_legal(mv))?;
+                let (zone_left, zone_top, zone_height) = self.drop_zone(pile);
+                let width = overlap(left, CARD_WIDTH, zone_left, CARD_WIDTH);
+                let height = overlap(top, CARD_HEIGHT, zone_top, zone_height);
+                Some((mv, width * height))
+            })
+            .filter(|&(_, area)| area > 0.0)
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(mv, _)| mv)
+    }
+
+    // Top-left corner and height of the area a pile takes drops on: its
+    // whole fanned column, or its slot when empty
+    fn drop_zone(&self, pile: Pile) -> (f64, f64, f64) {
+        self.cards_area(pile, self.game.pile(pile).len())
     }
 
     fn celebrate_win(&self) {
-        // Clear the canvas
-        self.ctx.clear_rect(
-            0.0,
-            0.0,
-            self.canvas.width() as f64,
-            self.canvas.height() as f64,
-        );
+        let seconds = self.elapsed_seconds();
+        let mut lines = vec!["🎉 You Win! 🎉".to_string()];
+        match &self.vegas_bank {
+            Some(bank) => {
+                lines.push(format!("Won: {}", format_money(self.game.score())));
+                lines.push(format!("Bank: {}", format_money(bank.balance(&self.game))));
+            }
+            None => match self.time_bonus(seconds) {
+                Some(bonus) => {
+                    lines.push(format!("Score: {}", self.game.score() + bonus));
+                    lines.push(format!("Time bonus: {}", bonus));
+                }
+                None => lines.push(format!("Score: {}", self.game.score())),
+            },
+        }
+        lines.push(format!("Time: {}", format_time(seconds)));
 
         // Draw permanent "You Win!" text
-        self.ctx.set_font("48px Arial");
-        self.ctx.set_fill_style(&"gold".into());
-        self.ctx
-            .fill_text(
-                "🎉 You Win! 🎉",
-                self.canvas.width() as f64 / 2.0 - 120.0,
-                self.canvas.height() as f64 / 2.0,
-            )
-            .unwrap();
+        let board = self.panel.board;
+        draw_win_screen(&self.ctx, board, &lines);
 
         // Add fade-out animation
         let ctx = self.ctx.clone();
-        let canvas = self.canvas.clone();
         let mut opacity = 1.0;
 
-        let closure: Rc<RefCell<Option<Closure<dyn FnMut()>>>> = Rc::new(RefCell::new(None)); // Specify the type explicitly
+        let closure: AnimationFrame = Rc::new(RefCell::new(None)); // Specify the type explicitly
         let closure_clone = closure.clone();
 
         *closure.borrow_mut() = Some(Closure::wrap(Box::new(move || {
             if opacity > 0.0 {
-                ctx.clear_rect(0.0, 0.0, canvas.width() as f64, canvas.height() as f64);
                 ctx.set_global_alpha(opacity);
-                ctx.set_font("48px Arial");
-                ctx.set_fill_style(&"gold".into());
-                ctx.fill_text(
-                    "🎉 You Win! 🎉",
-                    canvas.width() as f64 / 2.0 - 120.0,
-                    canvas.height() as f64 / 2.0,
-                )
-                .unwrap();
+                draw_win_screen(&ctx, board, &lines);
                 opacity -= 0.002; // Gradually reduce opacity
                 window()
                     .unwrap()
@@ -549,6 +1428,138 @@ impl GameState {
     }
 }
 
+// Clears the board and writes the win message, one line below the other
+fn draw_win_screen(ctx: &CanvasRenderingContext2d, (width, height): (f64, f64), lines: &[String]) {
+    ctx.clear_rect(0.0, 0.0, width, height);
+    ctx.set_font("48px Arial");
+    ctx.set_fill_style_str("gold");
+    for (i, line) in lines.iter().enumerate() {
+        ctx.fill_text(line, width / 2.0 - 120.0, height / 2.0 + i as f64 * 64.0)
+            .unwrap();
+    }
+}
+
+fn format_money(amount: i32) -> String {
+    if amount < 0 {
+        format!("-${}", -amount)
+    } else {
+        format!("${}", amount)
+    }
+}
+
+fn format_time(seconds: u64) -> String {
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+// Query parameter from the page URL, e.g. `?seed=12345&draw=3&scoring=vegas`
+// or `?variant=spider&suits=2`
+fn url_param(window: &web_sys::Window, name: &str) -> Option<String> {
+    let search = window.location().search().ok()?;
+    let params = UrlSearchParams::new_with_str(&search).ok()?;
+    params.get(name)
+}
+
+fn rules_from_url(window: &web_sys::Window) -> Rules {
+    // Spider has its own score and no waste, so the Klondike options do not
+    // apply
+    if url_param(window, "variant").as_deref() == Some("spider") {
+        let suits = match url_param(window, "suits").as_deref() {
+            Some("2") => 2,
+            Some("4") => 4,
+            _ => 1,
+        };
+        return Rules::spider(suits);
+    }
+    let draw_count = match url_param(window, "draw").as_deref() {
+        Some("3") => 3,
+        _ => 1,
+    };
+    let mut rules = match url_param(window, "scoring").as_deref() {
+        Some("vegas") => Rules::vegas(draw_count),
+        _ => Rules {
+            draw_count,
+            ..Rules::default()
+        },
+    };
+    if let Some(passes) = url_param(window, "passes").and_then(|p| p.parse().ok()) {
+        if passes > 0 {
+            rules.pass_limit = PassLimit::Passes(passes);
+        }
+    }
+    rules
+}
+
+fn copy_to_clipboard(text: &str) {
+    let _ = window().unwrap().navigator().clipboard().write_text(text);
+}
+
+fn alert(message: &str) {
+    let _ = window().unwrap().alert_with_message(message);
+}
+
+// The saved game, if there is one with at least one move made
+fn game_in_progress() -> Option<(Game, SavedGame)> {
+    let saved = SavedGame::decode(&storage::load(SAVED_GAME_KEY)?)?;
+    let game = saved.restore()?;
+    // Nothing to lose before the first move
+    if !game.can_undo() && !game.can_redo() {
+        return None;
+    }
+    Some((game, saved))
+}
+
+// The game in progress and its save, if the player wants it back
+fn resume_saved_game(
+    window: &web_sys::Window,
+    (game, saved): (Game, SavedGame),
+) -> Option<(Game, SavedGame)> {
+    if !window.confirm_with_message("Resume game?").unwrap_or(false) {
+        // Turning the game down gives it up
+        give_up(&saved.rules);
+        return None;
+    }
+    Some((game, saved))
+}
+
+// Counts the saved game as lost, before a new game replaces it
+fn give_up(rules: &Rules) {
+    let mut statistics = load_statistics();
+    statistics.record_loss(rules);
+    save_statistics(&statistics);
+}
+
+fn load_statistics() -> Statistics {
+    storage::load(STATISTICS_KEY)
+        .and_then(|text| Statistics::decode(&text))
+        .unwrap_or_default()
+}
+
+fn save_statistics(statistics: &Statistics) {
+    storage::save(STATISTICS_KEY, &statistics.encode());
+}
+
+fn random_seed() -> u64 {
+    (js_sys::Math::random() * u32::MAX as f64) as u64
+}
+
+// Fits the board again once the device pixel ratio moves away from its
+// current value, then watches for the next change
+fn watch_pixel_ratio(game_state: Rc<RefCell<GameState>>) {
+    let window = window().unwrap();
+    let query = format!("(resolution: {}dppx)", window.device_pixel_ratio());
+    let list = match window.match_media(&query) {
+        Ok(Some(list)) => list,
+        _ => return,
+    };
+    let watched = list.clone();
+    let on_change = Closure::once_into_js(move || {
+        watched.set_onchange(None);
+        game_state.borrow_mut().resize();
+        watch_pixel_ratio(game_state);
+    });
+    list.set_onchange(Some(on_change.unchecked_ref()));
+}
+
 #[wasm_bindgen]
 pub fn start() -> Result<(), JsValue> {
     let window = window().unwrap();
@@ -558,59 +1569,206 @@ pub fn start() -> Result<(), JsValue> {
         .unwrap()
         .dyn_into::<HtmlCanvasElement>()?;
 
-    // Set the canvas size to match the calculated dimensions
-    canvas.set_width(CANVAS_WIDTH as u32);
-    canvas.set_height(CANVAS_HEIGHT as u32);
-
     let ctx = canvas
         .get_context("2d")?
         .unwrap()
         .dyn_into::<CanvasRenderingContext2d>()?;
 
-    let game_state = Rc::new(RefCell::new(GameState::new(ctx, canvas.clone())));
+    // Offer to resume the last game before dealing a new one. A shared deal
+    // replaces a game of another deal, but reloading its page offers to
+    // resume it like any other.
+    let requested_seed = url_param(&window, "seed").and_then(|seed| seed.trim().parse().ok());
+    let resumed = match (game_in_progress(), requested_seed) {
+        (Some((_, saved)), Some(seed)) if saved.seed != seed => {
+            give_up(&saved.rules);
+            None
+        }
+        (Some(in_progress), _) => resume_saved_game(&window, in_progress),
+        (None, _) => None,
+    };
+    let game_state = Rc::new(RefCell::new(match resumed {
+        Some((game, saved)) => GameState::new(ctx, canvas.clone(), game, Some(&saved)),
+        None => {
+            let seed = requested_seed.unwrap_or_else(random_seed);
+            let game = Game::new(seed, rules_from_url(&window));
+            GameState::new(ctx, canvas.clone(), game, None)
+        }
+    }));
 
     {
         let game_state = game_state.clone();
-        let on_mousedown = Closure::wrap(Box::new(move |event: MouseEvent| {
-            let x = event.offset_x() as f64;
-            let y = event.offset_y() as f64;
-            game_state.borrow_mut().handle_mousedown(x, y);
+        let window = window.clone();
+        let on_pointerdown = Closure::wrap(Box::new(move |event: PointerEvent| {
+            let long_press = game_state.borrow_mut().handle_pointerdown(&event);
+            if let Some(number) = long_press {
+                let game_state = game_state.clone();
+                let on_long_press = Closure::once_into_js(move || {
+                    game_state.borrow_mut().handle_long_press(number);
+                });
+                window
+                    .set_timeout_with_callback_and_timeout_and_arguments_0(
+                        on_long_press.unchecked_ref(),
+                        LONG_PRESS_MS,
+                    )
+                    .unwrap();
+            }
         }) as Box<dyn FnMut(_)>);
 
         canvas
-            .add_event_listener_with_callback("mousedown", on_mousedown.as_ref().unchecked_ref())
+            .add_event_listener_with_callback(
+                "pointerdown",
+                on_pointerdown.as_ref().unchecked_ref(),
+            )
             .unwrap();
-        on_mousedown.forget();
+        on_pointerdown.forget();
     }
 
     {
         let game_state = game_state.clone();
-        let on_mousemove = Closure::wrap(Box::new(move |event: MouseEvent| {
-            let x = event.offset_x() as f64;
-            let y = event.offset_y() as f64;
-            game_state.borrow_mut().handle_mousemove(x, y);
+        let on_pointermove = Closure::wrap(Box::new(move |event: PointerEvent| {
+            game_state.borrow_mut().handle_pointermove(&event);
         }) as Box<dyn FnMut(_)>);
 
         canvas
-            .add_event_listener_with_callback("mousemove", on_mousemove.as_ref().unchecked_ref())
+            .add_event_listener_with_callback(
+                "pointermove",
+                on_pointermove.as_ref().unchecked_ref(),
+            )
+            .unwrap();
+        on_pointermove.forget();
+    }
+
+    {
+        let game_state = game_state.clone();
+        let on_pointerup = Closure::wrap(Box::new(move |event: PointerEvent| {
+            game_state.borrow_mut().handle_pointerup(&event);
+        }) as Box<dyn FnMut(_)>);
+
+        canvas
+            .add_event_listener_with_callback("pointerup", on_pointerup.as_ref().unchecked_ref())
+            .unwrap();
+        on_pointerup.forget();
+    }
+
+    {
+        let game_state = game_state.clone();
+        let on_pointercancel = Closure::wrap(Box::new(move |event: PointerEvent| {
+            game_state.borrow_mut().handle_pointercancel(&event);
+        }) as Box<dyn FnMut(_)>);
+
+        canvas
+            .add_event_listener_with_callback(
+                "pointercancel",
+                on_pointercancel.as_ref().unchecked_ref(),
+            )
             .unwrap();
-        on_mousemove.forget();
+        on_pointercancel.forget();
     }
 
     {
         let game_state = game_state.clone();
-        let on_mouseup = Closure::wrap(Box::new(move |event: MouseEvent| {
-            let x = event.offset_x() as f64;
-            let y = event.offset_y() as f64;
-            game_state.borrow_mut().handle_mouseup(x, y);
+        let on_dblclick = Closure::wrap(Box::new(move |event: MouseEvent| {
+            let mut game_state = game_state.borrow_mut();
+            let point = pointer::css_point(&game_state.canvas, &event);
+            let (x, y) = game_state.layout.board_point(point);
+            game_state.handle_dblclick(x, y);
         }) as Box<dyn FnMut(_)>);
 
         canvas
-            .add_event_listener_with_callback("mouseup", on_mouseup.as_ref().unchecked_ref())
+            .add_event_listener_with_callback("dblclick", on_dblclick.as_ref().unchecked_ref())
+            .unwrap();
+        on_dblclick.forget();
+    }
+
+    {
+        let game_state = game_state.clone();
+        let on_keydown = Closure::wrap(Box::new(move |event: KeyboardEvent| {
+            game_state.borrow_mut().handle_keydown(&event);
+        }) as Box<dyn FnMut(_)>);
+
+        window
+            .add_event_listener_with_callback("keydown", on_keydown.as_ref().unchecked_ref())
+            .unwrap();
+        on_keydown.forget();
+    }
+
+    {
+        // Pages are often closed or discarded while hidden, so save then too
+        let game_state = game_state.clone();
+        let on_visibilitychange = Closure::wrap(Box::new(move || {
+            game_state.borrow().save_game();
+        }) as Box<dyn FnMut()>);
+
+        document
+            .add_event_listener_with_callback(
+                "visibilitychange",
+                on_visibilitychange.as_ref().unchecked_ref(),
+            )
+            .unwrap();
+        on_visibilitychange.forget();
+    }
+
+    {
+        let game_state = game_state.clone();
+        let on_tick = Closure::wrap(Box::new(move || {
+            game_state.borrow_mut().tick();
+        }) as Box<dyn FnMut()>);
+
+        window
+            .set_interval_with_callback_and_timeout_and_arguments_0(
+                on_tick.as_ref().unchecked_ref(),
+                1000,
+            )
+            .unwrap();
+        on_tick.forget();
+    }
+
+    {
+        let game_state = game_state.clone();
+        let on_auto_complete = Closure::wrap(Box::new(move || {
+            game_state.borrow_mut().step_auto_complete();
+        }) as Box<dyn FnMut()>);
+
+        window
+            .set_interval_with_callback_and_timeout_and_arguments_0(
+                on_auto_complete.as_ref().unchecked_ref(),
+                AUTO_COMPLETE_STEP_MS,
+            )
+            .unwrap();
+        on_auto_complete.forget();
+    }
+
+    {
+        let game_state = game_state.clone();
+        let on_replay_frame = Closure::wrap(Box::new(move || {
+            game_state.borrow_mut().advance_replay();
+        }) as Box<dyn FnMut()>);
+
+        window
+            .set_interval_with_callback_and_timeout_and_arguments_0(
+                on_replay_frame.as_ref().unchecked_ref(),
+                REPLAY_FRAME_MS,
+            )
+            .unwrap();
+        on_replay_frame.forget();
+    }
+
+    {
+        let game_state = game_state.clone();
+        let on_resize = Closure::wrap(Box::new(move || {
+            game_state.borrow_mut().resize();
+        }) as Box<dyn FnMut()>);
+
+        window
+            .add_event_listener_with_callback("resize", on_resize.as_ref().unchecked_ref())
             .unwrap();
-        on_mouseup.forget();
+        on_resize.forget();
     }
+    // Zooming or moving the window to a screen of another density changes
+    // the pixel ratio, which does not always fire `resize`
+    watch_pixel_ratio(game_state.clone());
 
-    game_state.borrow_mut().render();
+    game_state.borrow().save_game();
+    game_state.borrow_mut().resize();
     Ok(())
 }
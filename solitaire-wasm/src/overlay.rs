@@ -0,0 +1,50 @@
+use web_sys::CanvasRenderingContext2d;
+
+// Outline drawn around part of the board
+pub struct Highlight {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub color: &'static str,
+}
+
+impl Highlight {
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d) {
+        ctx.set_line_width(6.0);
+        ctx.set_stroke_style_str(self.color);
+        ctx.stroke_rect(self.x, self.y, self.width, self.height);
+    }
+}
+
+// Transient annotations drawn over the board after everything else, so
+// they never move or hide the cards underneath
+#[derive(Default)]
+pub struct Overlay {
+    highlights: Vec<Highlight>,
+    expires_at: f64, // Date.now() after which nothing is drawn
+}
+
+impl Overlay {
+    pub fn show(&mut self, highlights: Vec<Highlight>, now: f64, duration_ms: f64) {
+        self.highlights = highlights;
+        self.expires_at = now + duration_ms;
+    }
+
+    pub fn clear(&mut self) {
+        self.highlights.clear();
+    }
+
+    pub fn is_visible(&self, now: f64) -> bool {
+        !self.highlights.is_empty() && now < self.expires_at
+    }
+
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d, now: f64) {
+        if !self.is_visible(now) {
+            return;
+        }
+        for highlight in &self.highlights {
+            highlight.draw(ctx);
+        }
+    }
+}
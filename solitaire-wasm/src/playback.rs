@@ -0,0 +1,172 @@
+use solitaire_core::replay::MoveLog;
+use solitaire_core::Game;
+use toolbar::{self, BUTTON_GAP, BUTTON_HEIGHT, BUTTON_WIDTH};
+use web_sys::CanvasRenderingContext2d;
+
+const SPEEDS: [f64; 4] = [1.0, 2.0, 4.0, 8.0];
+const BAR_TOP: f64 = BUTTON_HEIGHT + 20.0; // Scrub bar, below the buttons
+const BAR_WIDTH: f64 = 5.0 * BUTTON_WIDTH + 4.0 * BUTTON_GAP;
+const BAR_HEIGHT: f64 = 16.0;
+pub const SIZE: (f64, f64) = (BAR_WIDTH, BAR_TOP + BAR_HEIGHT + 50.0); // Down past the status line
+
+// Buttons of the replay controls, in a row above the scrub bar
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    Back,
+    PlayPause,
+    Forward,
+    Speed,
+    Close,
+}
+
+impl Control {
+    const ALL: [Control; 5] = [
+        Control::Back,
+        Control::PlayPause,
+        Control::Forward,
+        Control::Speed,
+        Control::Close,
+    ];
+
+    fn position(self, origin: (f64, f64)) -> (f64, f64) {
+        let index = Control::ALL.iter().position(|&c| c == self).unwrap();
+        (
+            origin.0 + index as f64 * (BUTTON_WIDTH + BUTTON_GAP),
+            origin.1,
+        )
+    }
+}
+
+// A finished game being watched again. The board shows the position after
+// `step` entries of the log, rebuilt from the deal whenever it changes.
+pub struct Playback {
+    pub log: MoveLog,
+    pub game: Game, // The finished game, put back when the replay closes
+    pub step: usize,
+    pub scrubbing: bool, // The scrub bar is being dragged
+    clock: f64,          // Replay time, in milliseconds since the deal
+    playing: bool,
+    speed: usize, // Index into SPEEDS
+    last_frame: f64,
+}
+
+impl Playback {
+    pub fn new(log: MoveLog, game: Game, now: f64) -> Self {
+        Playback {
+            log,
+            game,
+            step: 0,
+            scrubbing: false,
+            clock: 0.0,
+            playing: true,
+            speed: 0,
+            last_frame: now,
+        }
+    }
+
+    // Runs the clock forward while playing; true when the step changed
+    pub fn advance(&mut self, now: f64) -> bool {
+        let elapsed = now - self.last_frame;
+        self.last_frame = now;
+        if !self.playing {
+            return false;
+        }
+
+        self.clock += elapsed * SPEEDS[self.speed];
+        let entries = self.log.entries();
+        let step = self.step;
+        while self.step < entries.len() && entries[self.step].at as f64 <= self.clock {
+            self.step += 1;
+        }
+        if self.step == entries.len() {
+            self.playing = false;
+        }
+        self.step != step
+    }
+
+    pub fn toggle_playing(&mut self) {
+        // Playing again from the end starts over
+        if !self.playing && self.step == self.log.entries().len() {
+            self.step = 0;
+            self.clock = 0.0;
+        }
+        self.playing = !self.playing;
+    }
+
+    // Pauses and moves one entry back or forward
+    pub fn step_by(&mut self, forward: bool) {
+        self.playing = false;
+        self.step = if forward {
+            (self.step + 1).min(self.log.entries().len())
+        } else {
+            self.step.saturating_sub(1)
+        };
+        self.clock = match self.step {
+            0 => 0.0,
+            step => self.log.entries()[step - 1].at as f64,
+        };
+    }
+
+    pub fn next_speed(&mut self) {
+        self.speed = (self.speed + 1) % SPEEDS.len();
+    }
+
+    // Jumps to the point of the scrub bar under `x`
+    pub fn seek(&mut self, origin: (f64, f64), x: f64) {
+        let fraction = ((x - origin.0) / BAR_WIDTH).clamp(0.0, 1.0);
+        self.clock = fraction * self.log.duration() as f64;
+        self.step = self.log.steps_at(self.clock as u64);
+    }
+
+    pub fn control_at(&self, origin: (f64, f64), x: f64, y: f64) -> Option<Control> {
+        Control::ALL.iter().copied().find(|control| {
+            let (left, top) = control.position(origin);
+            x >= left && x <= left + BUTTON_WIDTH && y >= top && y <= top + BUTTON_HEIGHT
+        })
+    }
+
+    // The bar is easy to hit: a little taller than drawn
+    pub fn bar_contains(&self, origin: (f64, f64), x: f64, y: f64) -> bool {
+        let top = origin.1 + BAR_TOP;
+        x >= origin.0
+            && x <= origin.0 + BAR_WIDTH
+            && y >= top - 10.0
+            && y <= top + BAR_HEIGHT + 10.0
+    }
+
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d, origin: (f64, f64)) {
+        for control in Control::ALL {
+            let label = match control {
+                Control::Back => "Back".to_string(),
+                Control::PlayPause if self.playing => "Pause".to_string(),
+                Control::PlayPause => "Play".to_string(),
+                Control::Forward => "Step".to_string(),
+                Control::Speed => format!("{}x", SPEEDS[self.speed]),
+                Control::Close => "Close".to_string(),
+            };
+            let (left, top) = control.position(origin);
+            toolbar::draw_button(ctx, left, top, &label, true);
+        }
+
+        let duration = self.log.duration() as f64;
+        let fraction = if duration > 0.0 {
+            (self.clock / duration).min(1.0)
+        } else {
+            1.0
+        };
+        let top = origin.1 + BAR_TOP;
+        ctx.set_fill_style_str("darkgreen");
+        ctx.fill_rect(origin.0, top, BAR_WIDTH, BAR_HEIGHT);
+        ctx.set_fill_style_str("gold");
+        ctx.fill_rect(origin.0, top, BAR_WIDTH * fraction, BAR_HEIGHT);
+        ctx.set_stroke_style_str("black");
+        ctx.set_line_width(2.0);
+        ctx.stroke_rect(origin.0, top, BAR_WIDTH, BAR_HEIGHT);
+
+        let status = format!("Replay: move {} of {}", self.step, self.log.entries().len());
+        ctx.set_font("28px Arial");
+        ctx.set_fill_style_str("white");
+        ctx.fill_text(&status, origin.0, top + BAR_HEIGHT + 40.0)
+            .unwrap();
+    }
+}
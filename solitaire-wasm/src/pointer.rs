@@ -0,0 +1,21 @@
+use web_sys::{HtmlCanvasElement, MouseEvent};
+
+// The pointer pressing the board. Other pointers, such as a second finger,
+// are ignored until it lifts.
+#[derive(Clone, Copy)]
+pub struct Press {
+    pub pointer_id: i32,
+    pub number: u32, // Counts presses, so a late long-press timer can tell it is stale
+    pub slop: f64,   // Travel, in board units, below which the press is a tap
+}
+
+// Where `event` happened in CSS pixels from the top-left corner of the
+// canvas, wherever the page has put it. The client area leaves out any
+// border.
+pub fn css_point(canvas: &HtmlCanvasElement, event: &MouseEvent) -> (f64, f64) {
+    let rect = canvas.get_bounding_client_rect();
+    (
+        event.client_x() as f64 - rect.left() - canvas.client_left() as f64,
+        event.client_y() as f64 - rect.top() - canvas.client_top() as f64,
+    )
+}
@@ -0,0 +1,106 @@
+use format_time;
+use solitaire_core::stats::{self, Statistics};
+use std::collections::BTreeSet;
+use toolbar::{self, BUTTON_GAP, BUTTON_HEIGHT, BUTTON_WIDTH};
+use web_sys::CanvasRenderingContext2d;
+
+const TABLE_TOP: f64 = BUTTON_HEIGHT + 110.0; // Header row, below the buttons
+const ROW_HEIGHT: f64 = 48.0;
+// Board units the screen takes up right and down of its origin, with a row
+// for each of the two draw counts and three numbers of suits
+pub const SIZE: (f64, f64) = (1570.0, TABLE_TOP + 5.0 * ROW_HEIGHT);
+// Column titles and their left edges, relative to the screen origin
+const COLUMNS: [(&str, f64); 9] = [
+    ("Mode", 0.0),
+    ("Played", 300.0),
+    ("Won", 440.0),
+    ("Win %", 560.0),
+    ("Streak", 700.0),
+    ("Longest", 840.0),
+    ("Best time", 1000.0),
+    ("Best score", 1180.0),
+    ("Fewest moves", 1370.0),
+];
+
+// Buttons of the statistics screen, in a row below its title
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    Reset,
+    Close,
+}
+
+impl Control {
+    const ALL: [Control; 2] = [Control::Reset, Control::Close];
+
+    fn label(self) -> &'static str {
+        match self {
+            Control::Reset => "Reset",
+            Control::Close => "Close",
+        }
+    }
+
+    fn position(self, origin: (f64, f64)) -> (f64, f64) {
+        let index = Control::ALL.iter().position(|&c| c == self).unwrap();
+        (
+            origin.0 + index as f64 * (BUTTON_WIDTH + BUTTON_GAP),
+            origin.1 + 30.0,
+        )
+    }
+}
+
+pub fn control_at(origin: (f64, f64), x: f64, y: f64) -> Option<Control> {
+    Control::ALL.iter().copied().find(|control| {
+        let (left, top) = control.position(origin);
+        x >= left && x <= left + BUTTON_WIDTH && y >= top && y <= top + BUTTON_HEIGHT
+    })
+}
+
+// One row per mode played so far, plus the mode of the current game even
+// before its first result, which is picked out in gold
+pub fn draw(
+    ctx: &CanvasRenderingContext2d,
+    origin: (f64, f64),
+    statistics: &Statistics,
+    current_mode: &str,
+) {
+    ctx.set_font("40px Arial");
+    ctx.set_fill_style_str("white");
+    ctx.fill_text("Statistics", origin.0, origin.1).unwrap();
+    for control in Control::ALL {
+        let (left, top) = control.position(origin);
+        toolbar::draw_button(ctx, left, top, control.label(), true);
+    }
+
+    ctx.set_font("28px Arial");
+    for (title, left) in COLUMNS {
+        ctx.fill_text(title, origin.0 + left, origin.1 + TABLE_TOP)
+            .unwrap();
+    }
+
+    let modes: BTreeSet<&str> = statistics.modes().chain([current_mode]).collect();
+    let best = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+    for (i, mode) in modes.into_iter().enumerate() {
+        let tally = statistics.tally(mode);
+        let cells = [
+            stats::mode_label(mode),
+            tally.played.to_string(),
+            tally.won.to_string(),
+            format!("{:.0}%", tally.win_rate()),
+            tally.streak.to_string(),
+            tally.longest_streak.to_string(),
+            best(tally.best_time.map(format_time)),
+            best(tally.best_score.map(|score| score.to_string())),
+            best(tally.fewest_moves.map(|moves| moves.to_string())),
+        ];
+
+        let top = origin.1 + TABLE_TOP + (i + 1) as f64 * ROW_HEIGHT;
+        ctx.set_fill_style_str(if mode == current_mode {
+            "gold"
+        } else {
+            "white"
+        });
+        for (cell, (_, left)) in cells.iter().zip(COLUMNS) {
+            ctx.fill_text(cell, origin.0 + left, top).unwrap();
+        }
+    }
+}
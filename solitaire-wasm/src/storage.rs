@@ -0,0 +1,23 @@
+use web_sys::{window, Storage};
+
+// localStorage may be missing or blocked (e.g. private browsing); the game
+// then simply doesn't remember anything between page loads.
+fn local_storage() -> Option<Storage> {
+    window()?.local_storage().ok()?
+}
+
+pub fn load(key: &str) -> Option<String> {
+    local_storage()?.get_item(key).ok()?
+}
+
+pub fn save(key: &str, value: &str) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.set_item(key, value);
+    }
+}
+
+pub fn remove(key: &str) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(key);
+    }
+}
@@ -0,0 +1,105 @@
+use web_sys::CanvasRenderingContext2d;
+
+pub const BUTTON_WIDTH: f64 = 120.0;
+pub const BUTTON_HEIGHT: f64 = 40.0;
+pub const BUTTON_GAP: f64 = 10.0;
+
+// Where the toolbar goes: its top-left corner and how many buttons fit in
+// a row before it wraps to the next
+#[derive(Clone, Copy)]
+pub struct Placement {
+    pub origin: (f64, f64),
+    pub columns: usize,
+}
+
+impl Placement {
+    // Board units taken up by all the buttons
+    pub fn size(self) -> (f64, f64) {
+        let rows = Button::ALL.len().div_ceil(self.columns);
+        (
+            self.columns as f64 * (BUTTON_WIDTH + BUTTON_GAP) - BUTTON_GAP,
+            rows as f64 * (BUTTON_HEIGHT + BUTTON_GAP) - BUTTON_GAP,
+        )
+    }
+}
+
+// Buttons drawn on the canvas beside or below the piles
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Button {
+    NewGame,
+    Hint,
+    Undo,
+    Redo,
+    Export,
+    ExportDeal,
+    Import,
+    Replay,
+    Statistics,
+}
+
+impl Button {
+    pub const ALL: [Button; 9] = [
+        Button::NewGame,
+        Button::Hint,
+        Button::Undo,
+        Button::Redo,
+        Button::Export,
+        Button::ExportDeal,
+        Button::Import,
+        Button::Replay,
+        Button::Statistics,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Button::NewGame => "New",
+            Button::Hint => "Hint",
+            Button::Undo => "Undo",
+            Button::Redo => "Redo",
+            Button::Export => "Copy",
+            Button::ExportDeal => "Deal",
+            Button::Import => "Paste",
+            Button::Replay => "Replay",
+            Button::Statistics => "Stats",
+        }
+    }
+
+    // Top-left corner of the button, laid out in rows from left to right
+    fn position(self, placement: Placement) -> (f64, f64) {
+        let index = Button::ALL.iter().position(|&b| b == self).unwrap();
+        let (row, column) = (index / placement.columns, index % placement.columns);
+        (
+            placement.origin.0 + column as f64 * (BUTTON_WIDTH + BUTTON_GAP),
+            placement.origin.1 + row as f64 * (BUTTON_HEIGHT + BUTTON_GAP),
+        )
+    }
+
+    pub fn contains(self, placement: Placement, x: f64, y: f64) -> bool {
+        let (left, top) = self.position(placement);
+        x >= left && x <= left + BUTTON_WIDTH && y >= top && y <= top + BUTTON_HEIGHT
+    }
+
+    pub fn draw(self, ctx: &CanvasRenderingContext2d, placement: Placement, enabled: bool) {
+        let (left, top) = self.position(placement);
+        draw_button(ctx, left, top, self.label(), enabled);
+    }
+}
+
+// Also used for the replay controls, which share the toolbar's look
+pub fn draw_button(
+    ctx: &CanvasRenderingContext2d,
+    left: f64,
+    top: f64,
+    label: &str,
+    enabled: bool,
+) {
+    ctx.set_fill_style_str(if enabled { "white" } else { "lightgray" });
+    ctx.fill_rect(left, top, BUTTON_WIDTH, BUTTON_HEIGHT);
+    ctx.set_stroke_style_str("black");
+    ctx.set_line_width(2.0);
+    ctx.stroke_rect(left, top, BUTTON_WIDTH, BUTTON_HEIGHT);
+
+    ctx.set_font("24px Arial");
+    ctx.set_fill_style_str(if enabled { "black" } else { "gray" });
+    ctx.fill_text(label, left + 20.0, top + 28.0).unwrap();
+}